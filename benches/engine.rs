@@ -0,0 +1,123 @@
+//! Criterion benchmarks for risk engine hot paths
+//!
+//! Each benchmark runs against a production-size engine (MAX_ACCOUNTS = 4096)
+//! populated with 1,000 open positions against a single LP.
+//!
+//! Run with: cargo bench --bench engine
+//! Compare against a saved baseline:
+//!   cargo bench --bench engine -- --save-baseline main
+//!   cargo bench --bench engine -- --baseline main
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use percolator::*;
+
+const N_USERS: usize = 1_000;
+const PRICE: u64 = 1_000_000;
+
+fn bench_params() -> RiskParams {
+    RiskParams {
+        warmup_period_slots: 100,
+        maintenance_margin_bps: 500, // 5%
+        initial_margin_bps: 1000,    // 10%
+        trading_fee_bps: 10,         // 0.1%
+        max_accounts: MAX_ACCOUNTS as u64,
+        new_account_fee: 0,
+        risk_reduction_threshold: 0,
+        maintenance_fee_per_slot: 0,
+        max_crank_staleness_slots: u64::MAX,
+        liquidation_fee_bps: 50,
+        liquidation_fee_cap: 100_000,
+        liquidation_buffer_bps: 100,
+        min_liquidation_abs: 100_000,
+    }
+}
+
+/// Engine with one deep LP and N_USERS alternating long/short users,
+/// each opened at PRICE with ~11x leverage (close to maintenance).
+fn populated_engine() -> (Box<RiskEngine>, u16, Vec<u16>) {
+    let mut engine = Box::new(RiskEngine::new(bench_params()));
+    let lp = engine.add_lp([1u8; 32], [2u8; 32], 0).unwrap();
+    engine.deposit(lp, 1_000_000_000_000).unwrap();
+
+    let mut users = Vec::with_capacity(N_USERS);
+    for i in 0..N_USERS {
+        let u = engine.add_user(0).unwrap();
+        engine.deposit(u, 1_000_000).unwrap();
+        let size: i128 = if i % 2 == 0 { 9_000_000 } else { -9_000_000 };
+        engine
+            .execute_trade(&NoOpMatcher, lp, u, 0, PRICE, size)
+            .unwrap();
+        users.push(u);
+    }
+    (engine, lp, users)
+}
+
+fn bench_execute_trade(c: &mut Criterion) {
+    let (mut engine, lp, _users) = populated_engine();
+    // Dedicated deep-pocketed trader so fees never drain it below margin
+    let user = engine.add_user(0).unwrap();
+    engine.deposit(user, 1_000_000_000_000).unwrap();
+    c.bench_function("execute_trade/round_trip", |b| {
+        b.iter(|| {
+            engine
+                .execute_trade(&NoOpMatcher, lp, user, 0, PRICE, black_box(1_000))
+                .unwrap();
+            engine
+                .execute_trade(&NoOpMatcher, lp, user, 0, PRICE, black_box(-1_000))
+                .unwrap();
+        })
+    });
+}
+
+fn bench_crank_sweep(c: &mut Criterion) {
+    let (engine, _lp, _users) = populated_engine();
+
+    // Full sweep at an unchanged price: no liquidations, pure scan cost
+    c.bench_function("keeper_crank/full_sweep_1k_healthy", |b| {
+        b.iter_batched(
+            || engine.clone(),
+            |mut e| {
+                for step in 0..NUM_STEPS as u64 {
+                    e.keeper_crank(u16::MAX, 1 + step, PRICE, 0, false).unwrap();
+                }
+                e
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    // 8% drop: every long is liquidated during the sweep
+    c.bench_function("keeper_crank/full_sweep_1k_liquidations", |b| {
+        b.iter_batched(
+            || engine.clone(),
+            |mut e| {
+                for step in 0..NUM_STEPS as u64 {
+                    e.keeper_crank(u16::MAX, 1 + step, PRICE * 92 / 100, 0, false)
+                        .unwrap();
+                }
+                e
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_funding(c: &mut Criterion) {
+    let (engine, _lp, users) = populated_engine();
+    c.bench_function("funding/accrue_and_touch_1k", |b| {
+        b.iter_batched(
+            || engine.clone(),
+            |mut e| {
+                e.accrue_funding(100, PRICE, black_box(5)).unwrap();
+                for &u in &users {
+                    e.touch_account(u).unwrap();
+                }
+                e
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_execute_trade, bench_crank_sweep, bench_funding);
+criterion_main!(benches);
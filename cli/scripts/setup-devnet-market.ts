@@ -52,6 +52,7 @@ import {
 import { deriveVaultAuthority, deriveLpPda } from "../src/solana/pda.js";
 import { parseHeader, parseConfig, parseEngine, parseUsedIndices } from "../src/solana/slab.js";
 import { buildIx } from "../src/runtime/tx.js";
+import { ensureRent, formatRentCheck, SLAB_SIZE, MATCHER_CTX_SIZE } from "../src/runtime/rent.js";
 
 // ============================================================================
 // CONSTANTS
@@ -63,12 +64,6 @@ const CHAINLINK_SOL_USD = new PublicKey("99B2bTijsU6f1GCT73HmdR7HCFFjGMBcPZY6jZ9
 // Program IDs
 const PROGRAM_ID = new PublicKey("AT2XFGzcQ2vVHkW5xpnqhs8NvfCUq5EmEcky5KE9EhnA");
 const MATCHER_PROGRAM_ID = new PublicKey("4HcGCsyjAqnFua5ccuXyt8KRRQzKFbGTJkVChpS7Yfzy");
-const MATCHER_CTX_SIZE = 320;
-
-// Market parameters
-// SLAB_SIZE = HEADER_LEN + CONFIG_LEN (aligned) + ENGINE_LEN
-// Updated to match percolator-prog SLAB_LEN constant
-const SLAB_SIZE = 1111384;
 
 // Funding amounts (in lamports with 9 decimals for wrapped SOL)
 const INSURANCE_FUND_AMOUNT = 1_000_000_000n;  // 1 SOL
@@ -138,6 +133,13 @@ async function main() {
   const mint = NATIVE_MINT;
   console.log(`  Mint: ${mint.toBase58()} (Wrapped SOL)`);
 
+  // Make sure the payer can cover rent for every account we create
+  const rentCheck = await ensureRent(connection, payer.publicKey, [
+    { label: "slab", space: SLAB_SIZE },
+    { label: "matcher context", space: MATCHER_CTX_SIZE },
+  ]);
+  console.log(formatRentCheck(rentCheck, payer.publicKey));
+
   // Create slab account
   console.log("\nStep 3: Creating slab account...");
   const slab = Keypair.generate();
@@ -186,6 +186,22 @@ export const ACCOUNTS_UPDATE_CONFIG: readonly AccountSpec[] = [
   { name: "slab", signer: false, writable: true },
 ] as const;
 
+/**
+ * SetReduceOnly: 2 accounts
+ */
+export const ACCOUNTS_SET_REDUCE_ONLY: readonly AccountSpec[] = [
+  { name: "admin", signer: true, writable: false },
+  { name: "slab", signer: false, writable: true },
+] as const;
+
+/**
+ * SetOracleParams: 2 accounts
+ */
+export const ACCOUNTS_SET_ORACLE_PARAMS: readonly AccountSpec[] = [
+  { name: "admin", signer: true, writable: false },
+  { name: "slab", signer: false, writable: true },
+] as const;
+
 // ============================================================================
 // ACCOUNT META BUILDERS
 // ============================================================================
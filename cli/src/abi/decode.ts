@@ -0,0 +1,345 @@
+import { PublicKey } from "@solana/web3.js";
+import {
+  AccountSpec,
+  ACCOUNTS_INIT_MARKET,
+  ACCOUNTS_INIT_USER,
+  ACCOUNTS_INIT_LP,
+  ACCOUNTS_DEPOSIT_COLLATERAL,
+  ACCOUNTS_WITHDRAW_COLLATERAL,
+  ACCOUNTS_KEEPER_CRANK,
+  ACCOUNTS_TRADE_NOCPI,
+  ACCOUNTS_LIQUIDATE_AT_ORACLE,
+  ACCOUNTS_CLOSE_ACCOUNT,
+  ACCOUNTS_TOPUP_INSURANCE,
+  ACCOUNTS_TRADE_CPI,
+  ACCOUNTS_SET_RISK_THRESHOLD,
+  ACCOUNTS_UPDATE_ADMIN,
+  ACCOUNTS_CLOSE_SLAB,
+  ACCOUNTS_UPDATE_CONFIG,
+  ACCOUNTS_SET_REDUCE_ONLY,
+  ACCOUNTS_SET_ORACLE_PARAMS,
+} from "./accounts.js";
+import { IX_TAG } from "./instructions.js";
+
+/**
+ * Field types used by instruction layouts - mirror the Rust read_* helpers.
+ */
+type FieldType = "u8" | "u16" | "u32" | "u64" | "i64" | "u128" | "i128" | "pubkey" | "bytes32";
+
+const FIELD_SIZE: Record<FieldType, number> = {
+  u8: 1,
+  u16: 2,
+  u32: 4,
+  u64: 8,
+  i64: 8,
+  u128: 16,
+  i128: 16,
+  pubkey: 32,
+  bytes32: 32,
+};
+
+interface IxLayout {
+  name: string;
+  fields: readonly [string, FieldType][];
+  accounts: readonly AccountSpec[];
+}
+
+const RISK_PARAMS_FIELDS: [string, FieldType][] = [
+  ["warmupPeriodSlots", "u64"],
+  ["maintenanceMarginBps", "u64"],
+  ["initialMarginBps", "u64"],
+  ["tradingFeeBps", "u64"],
+  ["maxAccounts", "u64"],
+  ["newAccountFee", "u128"],
+  ["riskReductionThreshold", "u128"],
+  ["maintenanceFeePerSlot", "u128"],
+  ["maxCrankStalenessSlots", "u64"],
+  ["liquidationFeeBps", "u64"],
+  ["liquidationFeeCap", "u128"],
+  ["liquidationBufferBps", "u64"],
+  ["minLiquidationAbs", "u128"],
+];
+
+/**
+ * Instruction layouts by tag - exact match to Rust ix::Instruction::decode
+ */
+export const IX_LAYOUTS: Record<number, IxLayout> = {
+  [IX_TAG.InitMarket]: {
+    name: "InitMarket",
+    fields: [
+      ["admin", "pubkey"],
+      ["collateralMint", "pubkey"],
+      ["indexFeedId", "bytes32"],
+      ["maxStalenessSecs", "u64"],
+      ["confFilterBps", "u16"],
+      ["invert", "u8"],
+      ["unitScale", "u32"],
+      ...RISK_PARAMS_FIELDS,
+    ],
+    accounts: ACCOUNTS_INIT_MARKET,
+  },
+  [IX_TAG.InitUser]: {
+    name: "InitUser",
+    fields: [["feePayment", "u64"]],
+    accounts: ACCOUNTS_INIT_USER,
+  },
+  [IX_TAG.InitLP]: {
+    name: "InitLP",
+    fields: [
+      ["matcherProgram", "pubkey"],
+      ["matcherContext", "pubkey"],
+      ["feePayment", "u64"],
+    ],
+    accounts: ACCOUNTS_INIT_LP,
+  },
+  [IX_TAG.DepositCollateral]: {
+    name: "DepositCollateral",
+    fields: [
+      ["userIdx", "u16"],
+      ["amount", "u64"],
+    ],
+    accounts: ACCOUNTS_DEPOSIT_COLLATERAL,
+  },
+  [IX_TAG.WithdrawCollateral]: {
+    name: "WithdrawCollateral",
+    fields: [
+      ["userIdx", "u16"],
+      ["amount", "u64"],
+    ],
+    accounts: ACCOUNTS_WITHDRAW_COLLATERAL,
+  },
+  [IX_TAG.KeeperCrank]: {
+    name: "KeeperCrank",
+    fields: [
+      ["callerIdx", "u16"],
+      ["allowPanic", "u8"],
+    ],
+    accounts: ACCOUNTS_KEEPER_CRANK,
+  },
+  [IX_TAG.TradeNoCpi]: {
+    name: "TradeNoCpi",
+    fields: [
+      ["lpIdx", "u16"],
+      ["userIdx", "u16"],
+      ["size", "i128"],
+    ],
+    accounts: ACCOUNTS_TRADE_NOCPI,
+  },
+  [IX_TAG.LiquidateAtOracle]: {
+    name: "LiquidateAtOracle",
+    fields: [["targetIdx", "u16"]],
+    accounts: ACCOUNTS_LIQUIDATE_AT_ORACLE,
+  },
+  [IX_TAG.CloseAccount]: {
+    name: "CloseAccount",
+    fields: [["userIdx", "u16"]],
+    accounts: ACCOUNTS_CLOSE_ACCOUNT,
+  },
+  [IX_TAG.TopUpInsurance]: {
+    name: "TopUpInsurance",
+    fields: [["amount", "u64"]],
+    accounts: ACCOUNTS_TOPUP_INSURANCE,
+  },
+  [IX_TAG.TradeCpi]: {
+    name: "TradeCpi",
+    fields: [
+      ["lpIdx", "u16"],
+      ["userIdx", "u16"],
+      ["size", "i128"],
+    ],
+    accounts: ACCOUNTS_TRADE_CPI,
+  },
+  [IX_TAG.SetRiskThreshold]: {
+    name: "SetRiskThreshold",
+    fields: [["newThreshold", "u128"]],
+    accounts: ACCOUNTS_SET_RISK_THRESHOLD,
+  },
+  [IX_TAG.UpdateAdmin]: {
+    name: "UpdateAdmin",
+    fields: [["newAdmin", "pubkey"]],
+    accounts: ACCOUNTS_UPDATE_ADMIN,
+  },
+  [IX_TAG.CloseSlab]: {
+    name: "CloseSlab",
+    fields: [],
+    accounts: ACCOUNTS_CLOSE_SLAB,
+  },
+  [IX_TAG.UpdateConfig]: {
+    name: "UpdateConfig",
+    fields: [
+      ["fundingHorizonSlots", "u64"],
+      ["fundingKBps", "u64"],
+      ["fundingInvScaleNotionalE6", "u128"],
+      ["fundingMaxPremiumBps", "i64"],
+      ["fundingMaxBpsPerSlot", "i64"],
+      ["threshFloor", "u128"],
+      ["threshRiskBps", "u64"],
+      ["threshUpdateIntervalSlots", "u64"],
+      ["threshStepBps", "u64"],
+      ["threshAlphaBps", "u64"],
+      ["threshMin", "u128"],
+      ["threshMax", "u128"],
+      ["threshMinStep", "u128"],
+    ],
+    accounts: ACCOUNTS_UPDATE_CONFIG,
+  },
+  [IX_TAG.SetReduceOnly]: {
+    name: "SetReduceOnly",
+    fields: [["enabled", "u8"]],
+    accounts: ACCOUNTS_SET_REDUCE_ONLY,
+  },
+  [IX_TAG.SetOracleParams]: {
+    name: "SetOracleParams",
+    fields: [
+      ["maxStalenessSecs", "u64"],
+      ["confFilterBps", "u16"],
+    ],
+    accounts: ACCOUNTS_SET_ORACLE_PARAMS,
+  },
+};
+
+export interface DecodedInstruction {
+  tag: number;
+  name: string;
+  fields: Record<string, string>;
+  /** Account names in processor order (extra accounts are unnamed) */
+  accountNames: string[];
+  /** Set when the data would be rejected by the program's decoder */
+  error?: string;
+}
+
+function readField(data: Buffer, off: number, ty: FieldType): string {
+  switch (ty) {
+    case "u8":
+      return data.readUInt8(off).toString();
+    case "u16":
+      return data.readUInt16LE(off).toString();
+    case "u32":
+      return data.readUInt32LE(off).toString();
+    case "u64":
+      return data.readBigUInt64LE(off).toString();
+    case "i64":
+      return data.readBigInt64LE(off).toString();
+    case "u128": {
+      const lo = data.readBigUInt64LE(off);
+      const hi = data.readBigUInt64LE(off + 8);
+      return ((hi << 64n) | lo).toString();
+    }
+    case "i128": {
+      const lo = data.readBigUInt64LE(off);
+      const hi = data.readBigInt64LE(off + 8);
+      return ((hi << 64n) + lo).toString();
+    }
+    case "pubkey":
+      return new PublicKey(data.subarray(off, off + 32)).toBase58();
+    case "bytes32":
+      return data.subarray(off, off + 32).toString("hex");
+  }
+}
+
+/**
+ * Decode Percolator instruction data into a named variant and fields.
+ * Never throws: malformed data is reported via `error` using the same
+ * rules as the on-chain decoder (TruncatedData / TrailingBytes).
+ */
+export function decodeInstruction(data: Buffer): DecodedInstruction {
+  if (data.length === 0) {
+    return { tag: -1, name: "Unknown", fields: {}, accountNames: [], error: "InvalidInstructionData (empty)" };
+  }
+
+  const tag = data[0];
+  const layout = IX_LAYOUTS[tag];
+  if (!layout) {
+    return { tag, name: `Unknown(${tag})`, fields: {}, accountNames: [], error: "InvalidInstructionData (unknown tag)" };
+  }
+
+  const fields: Record<string, string> = {};
+  const accountNames = layout.accounts.map((a) => a.name);
+  let off = 1;
+  for (const [name, ty] of layout.fields) {
+    const size = FIELD_SIZE[ty];
+    if (off + size > data.length) {
+      return { tag, name: layout.name, fields, accountNames, error: `TruncatedData (at ${name})` };
+    }
+    fields[name] = readField(data, off, ty);
+    off += size;
+  }
+
+  if (off !== data.length) {
+    return {
+      tag,
+      name: layout.name,
+      fields,
+      accountNames,
+      error: `TrailingBytes (${data.length - off} extra)`,
+    };
+  }
+
+  return { tag, name: layout.name, fields, accountNames };
+}
+
+const BASE58_ALPHABET = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/**
+ * Decode a base58 string (RPC encoding for inner instruction data).
+ */
+export function decodeBase58(s: string): Buffer {
+  let n = 0n;
+  for (const ch of s) {
+    const v = BASE58_ALPHABET.indexOf(ch);
+    if (v < 0) throw new Error(`Invalid base58 character: ${ch}`);
+    n = n * 58n + BigInt(v);
+  }
+  const bytes: number[] = [];
+  while (n > 0n) {
+    bytes.unshift(Number(n & 0xffn));
+    n >>= 8n;
+  }
+  // Leading '1's encode leading zero bytes
+  for (const ch of s) {
+    if (ch !== "1") break;
+    bytes.unshift(0);
+  }
+  return Buffer.from(bytes);
+}
+
+/** percolator-prog TRADE_RESULT_VERSION / TRADE_RESULT_LEN */
+export const TRADE_RESULT_VERSION = 1;
+export const TRADE_RESULT_LEN = 96;
+
+/**
+ * Fill outcome published via set_return_data by TradeNoCpi / TradeCpi.
+ */
+export interface TradeResult {
+  lpIdx: number;
+  userIdx: number;
+  execPriceE6: bigint;
+  execSize: bigint;
+  fee: bigint;
+  userPosition: bigint;
+  userEntryPriceE6: bigint;
+  oraclePriceE6: bigint;
+  userCapital: bigint;
+}
+
+/**
+ * Decode trade return data. Returns null for anything that isn't a
+ * trade result (wrong length or version).
+ */
+export function decodeTradeResult(data: Buffer): TradeResult | null {
+  if (data.length < TRADE_RESULT_LEN || data.readUInt32LE(0) !== TRADE_RESULT_VERSION) {
+    return null;
+  }
+  const big = (off: number, ty: FieldType) => BigInt(readField(data, off, ty));
+  return {
+    lpIdx: data.readUInt16LE(4),
+    userIdx: data.readUInt16LE(6),
+    execPriceE6: big(8, "u64"),
+    execSize: big(16, "i128"),
+    fee: big(32, "u128"),
+    userPosition: big(48, "i128"),
+    userEntryPriceE6: big(64, "u64"),
+    oraclePriceE6: big(72, "u64"),
+    userCapital: big(80, "u128"),
+  };
+}
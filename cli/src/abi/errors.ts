@@ -18,7 +18,7 @@ export const PERCOLATOR_ERRORS: Record<number, ErrorInfo> = {
   },
   2: {
     name: "AlreadyInitialized",
-    hint: "This account is already initialized. Use a different account or skip initialization.",
+    hint: "This account is already initialized with different parameters. Use a different account or re-run with the original parameters.",
   },
   3: {
     name: "NotInitialized",
@@ -112,6 +112,26 @@ export const PERCOLATOR_ERRORS: Record<number, ErrorInfo> = {
     name: "InvalidTokenProgram",
     hint: "Invalid token program. Ensure SPL Token program is accessible.",
   },
+  26: {
+    name: "InvalidConfigParam",
+    hint: "A configuration parameter is out of range. Check the values passed to 'update-config'.",
+  },
+  27: {
+    name: "TruncatedData",
+    hint: "Instruction data is shorter than expected. The CLI may be out of date with the program - check for CLI updates.",
+  },
+  28: {
+    name: "TrailingBytes",
+    hint: "Instruction data has extra bytes after the last field. The CLI may be out of date with the program - check for CLI updates.",
+  },
+  29: {
+    name: "MissingAccount",
+    hint: "Not enough accounts were passed to the instruction. This is likely a CLI bug - please report it.",
+  },
+  30: {
+    name: "MarketReduceOnly",
+    hint: "Market is reduce-only. Only trades that shrink your position (without flipping it) are accepted.",
+  },
 };
 
 /**
@@ -135,26 +155,155 @@ export function getErrorHint(code: number): string | undefined {
   return PERCOLATOR_ERRORS[code]?.hint;
 }
 
-/**
- * Parse error from transaction logs.
- * Looks for "Program ... failed: custom program error: 0x..."
- */
-export function parseErrorFromLogs(logs: string[]): {
+export interface DecodedError {
   code: number;
   name: string;
   hint?: string;
-} | null {
+}
+
+function describe(code: number): DecodedError {
+  const info = decodeError(code);
+  return {
+    code,
+    name: info?.name ?? `Unknown(${code})`,
+    hint: info?.hint,
+  };
+}
+
+const FAILED_LINE_RE = /^Program (\w+) failed: (.*)$/;
+const CUSTOM_RE = /custom program error: 0x([0-9a-fA-F]+)/;
+
+/**
+ * Decode a failure reason such as "custom program error: 0xe". The caller
+ * must already know the reason came from percolator.
+ */
+export function decodeCustomError(reason: string): DecodedError | null {
+  const match = reason.match(CUSTOM_RE);
+  return match ? describe(parseInt(match[1], 16)) : null;
+}
+
+/**
+ * Parse error from transaction logs.
+ * The runtime logs "Program X failed: ..." for every frame the error
+ * unwinds through, innermost first, so only the first such line names the
+ * program that raised it. Returns null unless that program is `programId`.
+ */
+export function parseErrorFromLogs(logs: string[], programId: string): DecodedError | null {
   for (const log of logs) {
-    const match = log.match(/custom program error: 0x([0-9a-fA-F]+)/);
-    if (match) {
-      const code = parseInt(match[1], 16);
-      const info = decodeError(code);
-      return {
-        code,
-        name: info?.name ?? `Unknown(${code})`,
-        hint: info?.hint,
-      };
+    const failed = log.match(FAILED_LINE_RE);
+    if (failed) {
+      return failed[1] === programId ? decodeCustomError(failed[2]) : null;
     }
   }
   return null;
 }
+
+/**
+ * True if the logs name a failing program, i.e. parseErrorFromLogs has
+ * the full answer and the TransactionError should not be consulted.
+ */
+export function logsHaveFailure(logs: string[]): boolean {
+  return logs.some((l) => FAILED_LINE_RE.test(l));
+}
+
+/**
+ * Parse error from a TransactionError value, e.g.
+ * {"InstructionError":[0,{"Custom":27}]}, or a send error message.
+ * Used when logs are unavailable (send failures, truncated logs).
+ * The instruction index is resolved against `ixProgramIds` (the
+ * transaction's top-level programs, compute budget included) and only
+ * percolator instructions are decoded.
+ */
+export function parseErrorFromTxErr(
+  err: unknown,
+  programId: string,
+  ixProgramIds: string[]
+): DecodedError | null {
+  if (err === null || err === undefined) return null;
+  const text = typeof err === "string" ? err : JSON.stringify(err);
+  let index: number;
+  let code: number;
+  const custom = text.match(/"InstructionError"\s*:\s*\[\s*(\d+)\s*,\s*\{\s*"Custom"\s*:\s*(\d+)/);
+  const message = text.match(/Instruction (\d+): custom program error: 0x([0-9a-fA-F]+)/);
+  if (custom) {
+    index = parseInt(custom[1], 10);
+    code = parseInt(custom[2], 10);
+  } else if (message) {
+    index = parseInt(message[1], 10);
+    code = parseInt(message[2], 16);
+  } else {
+    return null;
+  }
+  return ixProgramIds[index] === programId ? describe(code) : null;
+}
+
+/**
+ * Recognize runtime-level balance failures (rent / fees), which are not
+ * program errors and would otherwise surface as raw TransactionError JSON.
+ */
+export function parseRentFailure(
+  logs: string[],
+  err: unknown
+): { name: string; hint: string } | null {
+  const text = err === null || err === undefined
+    ? ""
+    : typeof err === "string" ? err : JSON.stringify(err);
+  if (text.includes("InsufficientFundsForRent")) {
+    return {
+      name: "InsufficientFundsForRent",
+      hint: "An account would be left below rent exemption. Fund the payer; percolator-cli rent-check shows the amount needed.",
+    };
+  }
+  if (text.includes("InsufficientFundsForFee")) {
+    return {
+      name: "InsufficientFundsForFee",
+      hint: "Payer cannot cover the transaction fee. Top up the payer wallet.",
+    };
+  }
+  if (
+    text.includes("no record of a prior credit") ||
+    logs.some((l) => l.includes("no record of a prior credit"))
+  ) {
+    return {
+      name: "PayerNotFunded",
+      hint: "Payer account has no SOL. Fund it (devnet: solana airdrop 2 <payer>).",
+    };
+  }
+  if (logs.some((l) => /insufficient lamports/i.test(l))) {
+    return {
+      name: "InsufficientLamports",
+      hint: "Payer cannot cover rent for a new account. Run percolator-cli rent-check to see the shortfall.",
+    };
+  }
+  return null;
+}
+
+/**
+ * Turn a failed transaction into a readable error + hint.
+ * Runtime rent/fee failures come first: a System Program transfer that
+ * runs out of lamports also logs a custom error (0x1). Then the program
+ * log line, then the TransactionError. Only errors raised by `programId`
+ * are decoded with the percolator table; anything else keeps its raw
+ * reason.
+ */
+export function describeFailure(
+  logs: string[],
+  txErr: unknown,
+  programId: string,
+  ixProgramIds: string[]
+): { err: string; hint?: string } {
+  const rent = parseRentFailure(logs, txErr);
+  if (rent) {
+    return { err: rent.name, hint: rent.hint };
+  }
+  const parsed = logsHaveFailure(logs)
+    ? parseErrorFromLogs(logs, programId)
+    : parseErrorFromTxErr(txErr, programId, ixProgramIds);
+  if (parsed) {
+    return {
+      err: `${parsed.name} (0x${parsed.code.toString(16)})`,
+      hint: parsed.hint,
+    };
+  }
+  return { err: typeof txErr === "string" ? txErr : JSON.stringify(txErr) };
+}
@@ -29,6 +29,8 @@ export const IX_TAG = {
   UpdateAdmin: 12,
   CloseSlab: 13,
   UpdateConfig: 14,
+  SetReduceOnly: 15,
+  SetOracleParams: 16,
 } as const;
 
 /**
@@ -279,6 +281,33 @@ export function encodeUpdateAdmin(args: UpdateAdminArgs): Buffer {
   return Buffer.concat([encU8(IX_TAG.UpdateAdmin), encPubkey(args.newAdmin)]);
 }
 
+/**
+ * SetReduceOnly instruction data (2 bytes)
+ */
+export interface SetReduceOnlyArgs {
+  enabled: boolean;
+}
+
+export function encodeSetReduceOnly(args: SetReduceOnlyArgs): Buffer {
+  return Buffer.concat([encU8(IX_TAG.SetReduceOnly), encU8(args.enabled ? 1 : 0)]);
+}
+
+/**
+ * SetOracleParams instruction data (11 bytes)
+ */
+export interface SetOracleParamsArgs {
+  maxStalenessSecs: bigint | string;
+  confFilterBps: number;
+}
+
+export function encodeSetOracleParams(args: SetOracleParamsArgs): Buffer {
+  return Buffer.concat([
+    encU8(IX_TAG.SetOracleParams),
+    encU64(args.maxStalenessSecs),
+    encU16(args.confFilterBps),
+  ]);
+}
+
 /**
  * CloseSlab instruction data (1 byte)
  */
@@ -10,10 +10,13 @@ import { registerWithdraw } from "./commands/withdraw.js";
 import { registerKeeperCrank } from "./commands/keeper-crank.js";
 import { registerTradeNocpi } from "./commands/trade-nocpi.js";
 import { registerTradeCpi } from "./commands/trade-cpi.js";
+import { registerTradeMulti } from "./commands/trade-multi.js";
 import { registerLiquidateAtOracle } from "./commands/liquidate-at-oracle.js";
 import { registerCloseAccount } from "./commands/close-account.js";
 import { registerTopupInsurance } from "./commands/topup-insurance.js";
 import { registerSetRiskThreshold } from "./commands/set-risk-threshold.js";
+import { registerSetReduceOnly } from "./commands/set-reduce-only.js";
+import { registerSetOracleParams } from "./commands/set-oracle-params.js";
 import { registerUpdateAdmin } from "./commands/update-admin.js";
 import { registerCloseSlab } from "./commands/close-slab.js";
 import { registerCloseAllSlabs } from "./commands/close-all-slabs.js";
@@ -30,6 +33,13 @@ import { registerSlabBitmap } from "./commands/slab-bitmap.js";
 import { registerAuditCu } from "./commands/audit-cu.js";
 import { registerBestPrice } from "./commands/best-price.js";
 import { registerUpdateConfig } from "./commands/update-config.js";
+import { registerDecodeTx } from "./commands/decode-tx.js";
+import { registerQuote } from "./commands/quote.js";
+import { registerRoutePreview } from "./commands/route-preview.js";
+import { registerFundingPredict } from "./commands/funding-predict.js";
+import { registerStress } from "./commands/stress.js";
+import { registerBooks } from "./commands/books.js";
+import { registerRentCheck } from "./commands/rent-check.js";
 
 export function createCli(): Command {
   const program = new Command();
@@ -50,7 +60,8 @@ export function createCli(): Command {
       "Commitment level: processed, confirmed, finalized"
     )
     .option("--json", "Output in JSON format")
-    .option("--simulate", "Simulate transaction without sending");
+    .option("--simulate", "Simulate transaction without sending")
+    .option("-v, --verbose", "Show CPI trace and per-program compute units after each transaction");
 
   // Register all commands
   registerInitMarket(program);
@@ -61,10 +72,13 @@ export function createCli(): Command {
   registerKeeperCrank(program);
   registerTradeNocpi(program);
   registerTradeCpi(program);
+  registerTradeMulti(program);
   registerLiquidateAtOracle(program);
   registerCloseAccount(program);
   registerTopupInsurance(program);
   registerSetRiskThreshold(program);
+  registerSetReduceOnly(program);
+  registerSetOracleParams(program);
   registerUpdateAdmin(program);
   registerCloseSlab(program);
   registerCloseAllSlabs(program);
@@ -81,6 +95,13 @@ export function createCli(): Command {
   registerAuditCu(program);
   registerBestPrice(program);
   registerUpdateConfig(program);
+  registerDecodeTx(program);
+  registerQuote(program);
+  registerRoutePreview(program);
+  registerFundingPredict(program);
+  registerStress(program);
+  registerBooks(program);
+  registerRentCheck(program);
 
   return program;
 }
@@ -98,5 +119,6 @@ export function getGlobalFlags(cmd: Command): GlobalFlags {
     commitment: opts.commitment,
     json: opts.json ?? false,
     simulate: opts.simulate ?? false,
+    verbose: opts.verbose ?? false,
   };
 }
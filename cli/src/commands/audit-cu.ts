@@ -19,6 +19,8 @@ const DEFAULT_BUDGETS: Record<string, number> = {
   "close-account": 80_000, // Similar to liquidation
   "topup-insurance": 30_000,
   "set-risk-threshold": 20_000,
+  "set-reduce-only": 20_000,
+  "set-oracle-params": 20_000,
   "update-admin": 20_000,
 };
 
@@ -5,10 +5,7 @@ import { loadConfig } from "../config.js";
 import { createContext } from "../runtime/context.js";
 import { fetchSlab, parseUsedIndices, parseAccount, AccountKind } from "../solana/slab.js";
 import { validatePublicKey } from "../validation.js";
-
-// Matcher constants
-const PASSIVE_MATCHER_EDGE_BPS = 50n;
-const BPS_DENOM = 10000n;
+import { computePassiveQuote } from "../runtime/quote.js";
 
 interface LpQuote {
   lpIndex: number;
@@ -20,13 +17,6 @@ interface LpQuote {
   position: bigint;
 }
 
-function computePassiveQuote(oraclePrice: bigint, edgeBps: bigint): { bid: bigint; ask: bigint } {
-  const bid = (oraclePrice * (BPS_DENOM - edgeBps)) / BPS_DENOM;
-  const askNumer = oraclePrice * (BPS_DENOM + edgeBps);
-  const ask = (askNumer + BPS_DENOM - 1n) / BPS_DENOM;
-  return { bid, ask };
-}
-
 async function getChainlinkPrice(connection: any, oracle: PublicKey): Promise<{ price: bigint; decimals: number }> {
   const info = await connection.getAccountInfo(oracle);
   if (!info) throw new Error("Oracle not found");
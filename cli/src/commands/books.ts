@@ -0,0 +1,98 @@
+import { Command } from "commander";
+import { unpackAccount } from "@solana/spl-token";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { parseHeader, parseConfig, parseEngine, parseAllAccounts } from "../solana/slab.js";
+import { parseOraclePriceE6, toEnginePriceE6 } from "../solana/oracle.js";
+import { computeBooks, formatBooks } from "../runtime/books.js";
+import { validatePublicKey } from "../validation.js";
+
+export function registerBooks(program: Command): void {
+  program
+    .command("books")
+    .description("Export an accounting snapshot of a market (vault vs capital, PnL and insurance)")
+    .requiredOption("--slab <pubkey>", "Slab account public key")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabPk = validatePublicKey(opts.slab, "--slab");
+
+      // Read the slab, then slab, token vault and oracle together so both
+      // sides of the ledger come from the same slot
+      const slabInfo = await ctx.connection.getAccountInfo(slabPk);
+      if (!slabInfo) {
+        throw new Error(`Slab account not found: ${slabPk.toBase58()}`);
+      }
+      const { vaultPubkey: vaultPk, indexFeedId: oraclePk } = parseConfig(Buffer.from(slabInfo.data));
+      const { context, value } = await ctx.connection.getMultipleAccountsInfoAndContext(
+        [slabPk, vaultPk, oraclePk],
+        ctx.commitment
+      );
+      const [slab, vault, oracle] = value;
+      if (!slab) {
+        throw new Error(`Slab account not found: ${slabPk.toBase58()}`);
+      }
+      const data = Buffer.from(slab.data);
+      const tokenVault = vault ? unpackAccount(vaultPk, vault).amount : null;
+      const mkt = parseConfig(data);
+
+      let oraclePriceE6: bigint | null = null;
+      if (oracle) {
+        try {
+          const raw = parseOraclePriceE6(oracle.owner, Buffer.from(oracle.data));
+          oraclePriceE6 = toEnginePriceE6(raw, mkt.invert, mkt.unitScale);
+        } catch {
+          // Unreadable oracle: report the books without unrealized PnL
+        }
+      }
+
+      const engine = parseEngine(data);
+      const accounts = parseAllAccounts(data).map(({ account }) => account);
+      const books = computeBooks(engine, accounts, {
+        tokenVault,
+        unitScale: mkt.unitScale,
+        dustBase: parseHeader(data).dustBase,
+        oraclePriceE6,
+      });
+
+      if (flags.json) {
+        console.log(
+          JSON.stringify(
+            {
+              slab: slabPk.toBase58(),
+              slot: context.slot,
+              accounts: accounts.length,
+              vault: books.vault.toString(),
+              lossAccum: books.lossAccum.toString(),
+              assets: books.assets.toString(),
+              totalCapital: books.totalCapital.toString(),
+              netPnl: books.netPnl.toString(),
+              insurance: books.insurance.toString(),
+              feeRevenue: books.feeRevenue.toString(),
+              liabilities: books.liabilities.toString(),
+              surplus: books.surplus.toString(),
+              oraclePriceE6: oraclePriceE6?.toString() ?? null,
+              unrealizedPnl: books.unrealizedPnl?.toString() ?? null,
+              unitScale: books.unitScale,
+              dustBase: books.dustBase.toString(),
+              vaultBase: books.vaultBase.toString(),
+              tokenVault: books.tokenVault?.toString() ?? null,
+              tokenVaultDiff: books.tokenVaultDiff?.toString() ?? null,
+              balanced: books.balanced,
+            },
+            null,
+            2
+          )
+        );
+      } else {
+        console.log(`Books for ${slabPk.toBase58()} at slot ${context.slot} (${accounts.length} accounts):`);
+        console.log(formatBooks(books));
+      }
+      if (!books.balanced) {
+        process.exitCode = 1;
+      }
+    });
+}
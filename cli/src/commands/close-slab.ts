@@ -46,6 +46,6 @@ export function registerCloseSlab(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
@@ -0,0 +1,199 @@
+import { Command } from "commander";
+import { PublicKey } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { decodeInstruction, decodeBase58, DecodedInstruction } from "../abi/decode.js";
+import {
+  parseErrorFromLogs,
+  parseErrorFromTxErr,
+  logsHaveFailure,
+  DecodedError,
+} from "../abi/errors.js";
+import { parseLogTree, findFailure, failedInnerIndex, formatLogTree } from "../runtime/logs.js";
+
+interface IxSummary {
+  index: string;
+  programId: string;
+  accounts: { name: string; pubkey: string }[];
+  decoded?: DecodedInstruction;
+  dataLen: number;
+  failed: boolean;
+}
+
+function summarize(
+  index: string,
+  programId: PublicKey,
+  accountKeys: PublicKey[],
+  data: Buffer,
+  percolatorId: PublicKey,
+  failed: boolean
+): IxSummary {
+  const decoded = programId.equals(percolatorId) ? decodeInstruction(data) : undefined;
+  return {
+    index,
+    programId: programId.toBase58(),
+    accounts: accountKeys.map((k, i) => ({
+      name: decoded?.accountNames[i] ?? `#${i}`,
+      pubkey: k.toBase58(),
+    })),
+    decoded,
+    dataLen: data.length,
+    failed,
+  };
+}
+
+export function registerDecodeTx(program: Command): void {
+  program
+    .command("decode-tx")
+    .description("Fetch a transaction and decode its instructions, CPI tree, events and failure")
+    .argument("<signature>", "Transaction signature")
+    .action(async (signature: string, _opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const tx = await ctx.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      if (!tx) {
+        throw new Error(`Transaction not found: ${signature}`);
+      }
+
+      const meta = tx.meta;
+      const message = tx.transaction.message;
+      const keys = message.getAccountKeys({
+        accountKeysFromLookups: meta?.loadedAddresses,
+      });
+      const key = (i: number) => keys.get(i) ?? PublicKey.default;
+
+      // Top-level instruction index that failed, from {"InstructionError":[idx, ...]}
+      const txErr = meta?.err as { InstructionError?: [number, unknown] } | null;
+      const failedIdx = txErr?.InstructionError?.[0];
+
+      const logs = meta?.logMessages ?? [];
+      const tree = parseLogTree(logs);
+      const failure = findFailure(tree);
+
+      // Inner instruction that failed, if a CPI raised the error. Roots map
+      // to top-level instructions only when the logs are complete.
+      const failedRoot =
+        failedIdx !== undefined && tree.length === message.compiledInstructions.length
+          ? tree[failedIdx]
+          : undefined;
+      const failedInner = failedRoot ? failedInnerIndex(failedRoot) : null;
+
+      const instructions: IxSummary[] = [];
+      message.compiledInstructions.forEach((ix, i) => {
+        instructions.push(
+          summarize(
+            `${i}`,
+            key(ix.programIdIndex),
+            ix.accountKeyIndexes.map(key),
+            Buffer.from(ix.data),
+            ctx.programId,
+            i === failedIdx
+          )
+        );
+        const inner = meta?.innerInstructions?.find((x) => x.index === i);
+        inner?.instructions.forEach((iix, j) => {
+          instructions.push(
+            summarize(
+              `${i}.${j}`,
+              key(iix.programIdIndex),
+              iix.accounts.map(key),
+              decodeBase58(iix.data),
+              ctx.programId,
+              i === failedIdx && j === failedInner
+            )
+          );
+        });
+      });
+
+      const percolatorId = ctx.programId.toBase58();
+      const ixProgramIds = message.compiledInstructions.map((ix) => key(ix.programIdIndex).toBase58());
+      // Percolator's table only applies when percolator raised the error;
+      // token or matcher failures keep their raw reason
+      let error: DecodedError | null = null;
+      if (meta?.err) {
+        if (failure) {
+          error = failure.programId === percolatorId ? parseErrorFromLogs(logs, percolatorId) : null;
+        } else if (!logsHaveFailure(logs)) {
+          error = parseErrorFromTxErr(meta.err, percolatorId, ixProgramIds);
+        }
+      }
+
+      const label = (p: string) => (p === percolatorId ? `percolator (${p})` : p);
+
+      if (flags.json) {
+        console.log(
+          JSON.stringify(
+            {
+              signature,
+              slot: tx.slot,
+              fee: meta?.fee,
+              unitsConsumed: meta?.computeUnitsConsumed,
+              err: meta?.err ?? null,
+              error,
+              failedInstruction: failedIdx ?? null,
+              failedProgram: failure?.programId ?? null,
+              instructions,
+              invocations: tree,
+            },
+            null,
+            2
+          )
+        );
+        return;
+      }
+
+      console.log(`Signature: ${signature}`);
+      console.log(`Slot:      ${tx.slot}`);
+      console.log(`Fee:       ${meta?.fee ?? "?"} lamports`);
+      if (meta?.computeUnitsConsumed !== undefined) {
+        console.log(`CU:        ${meta.computeUnitsConsumed.toLocaleString()}`);
+      }
+      console.log(`Status:    ${meta?.err ? "FAILED" : "success"}`);
+
+      console.log("\n--- Instructions ---");
+      for (const ix of instructions) {
+        const marker = ix.failed ? "  <-- FAILED" : "";
+        const name = ix.decoded ? ix.decoded.name : `${ix.dataLen} bytes`;
+        console.log(`[${ix.index}] ${label(ix.programId)}: ${name}${marker}`);
+        if (ix.decoded?.error) {
+          console.log(`      ! ${ix.decoded.error}`);
+        }
+        for (const [k, v] of Object.entries(ix.decoded?.fields ?? {})) {
+          console.log(`      ${k}: ${v}`);
+        }
+        if (ix.decoded) {
+          for (const a of ix.accounts) {
+            console.log(`      ${a.name}: ${a.pubkey}`);
+          }
+        }
+      }
+
+      console.log("\n--- Invocation tree ---");
+      formatLogTree(tree, label).forEach((l) => console.log(l));
+
+      if (meta?.err) {
+        console.log("\n--- Failure ---");
+        if (failedIdx !== undefined) {
+          console.log(`Instruction: ${failedIdx}`);
+        }
+        if (failure) {
+          console.log(`Program:     ${label(failure.programId)}`);
+          console.log(`Reason:      ${failure.error}`);
+        }
+        if (error) {
+          console.log(`Error:       ${error.name} (0x${error.code.toString(16)})`);
+          if (error.hint) {
+            console.log(`Hint:        ${error.hint}`);
+          }
+        } else {
+          console.log(`Error:       ${JSON.stringify(meta.err)}`);
+        }
+      }
+    });
+}
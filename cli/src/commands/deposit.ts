@@ -68,6 +68,6 @@ export function registerDeposit(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
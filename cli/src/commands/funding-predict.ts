@@ -0,0 +1,109 @@
+import { Command } from "commander";
+import { PublicKey } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import {
+  fetchSlab,
+  parseConfig,
+  parseEngine,
+  parseFundingParams,
+  parseAllAccounts,
+  parseAccount,
+  AccountKind,
+} from "../solana/slab.js";
+import { fetchEnginePriceE6 } from "../solana/oracle.js";
+import { predictFunding, fundingPayment } from "../runtime/funding.js";
+import { validatePublicKey, validateIndex, validateU64 } from "../validation.js";
+
+export function registerFundingPredict(program: Command): void {
+  program
+    .command("funding-predict")
+    .description("Predict the funding rate and payments the next crank will apply")
+    .requiredOption("--slab <pubkey>", "Slab account public key")
+    .option("--oracle <pubkey>", "Price oracle account (default: market index feed)")
+    .option("--slots <number>", "Predict for a crank this many slots from now", "0")
+    .option("--user-idx <number>", "Also show the payment for this account")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabPk = validatePublicKey(opts.slab, "--slab");
+      const ahead = validateU64(opts.slots, "--slots");
+
+      const [data, slot] = await Promise.all([
+        fetchSlab(ctx.connection, slabPk),
+        ctx.connection.getSlot(ctx.commitment),
+      ]);
+      const mktConfig = parseConfig(data);
+      const engine = parseEngine(data);
+      const params = parseFundingParams(data);
+      const oracle: PublicKey = opts.oracle
+        ? validatePublicKey(opts.oracle, "--oracle")
+        : mktConfig.indexFeedId;
+      const price = await fetchEnginePriceE6(ctx.connection, oracle, mktConfig.invert, mktConfig.unitScale);
+
+      // Engine keeps net_lp_pos as the sum of LP positions
+      const netLpPos = parseAllAccounts(data)
+        .filter(({ account }) => account.kind === AccountKind.LP)
+        .reduce((sum, { account }) => sum + account.positionSize, 0n);
+
+      const p = predictFunding(
+        netLpPos,
+        price,
+        params,
+        engine.fundingIndexQpbE6,
+        engine.lastFundingSlot,
+        BigInt(slot) + BigInt(ahead)
+      );
+
+      let user: { idx: number; position: bigint; payment: bigint } | null = null;
+      if (opts.userIdx !== undefined) {
+        const idx = validateIndex(opts.userIdx, "--user-idx");
+        const acc = parseAccount(data, idx);
+        user = {
+          idx,
+          position: acc.positionSize,
+          payment: fundingPayment(acc.positionSize, acc.fundingIndex, p.nextIndex),
+        };
+      }
+
+      if (flags.json) {
+        console.log(
+          JSON.stringify(
+            {
+              slot,
+              priceE6: price.toString(),
+              netLpPos: netLpPos.toString(),
+              rateBpsPerSlot: p.rateBpsPerSlot.toString(),
+              dtSlots: p.dt.toString(),
+              indexDelta: p.indexDelta.toString(),
+              nextIndex: p.nextIndex.toString(),
+              user: user && {
+                idx: user.idx,
+                position: user.position.toString(),
+                payment: user.payment.toString(),
+              },
+            },
+            null,
+            2
+          )
+        );
+        return;
+      }
+
+      const payer = p.rateBpsPerSlot > 0n ? "longs pay shorts" : p.rateBpsPerSlot < 0n ? "shorts pay longs" : "no funding";
+      console.log(`Funding prediction at slot ${BigInt(slot) + BigInt(ahead)}:`);
+      console.log(`  Price:            ${price}`);
+      console.log(`  LP net position:  ${netLpPos}`);
+      console.log(`  Rate:             ${p.rateBpsPerSlot} bps/slot (${payer})`);
+      console.log(`  Slots to accrue:  ${p.dt} (last funding at ${engine.lastFundingSlot})`);
+      console.log(`  Index delta:      ${p.indexDelta}`);
+      console.log(`  Per 1 unit long:  ${fundingPayment(1n, 0n, p.indexDelta)}`);
+      if (user) {
+        const verb = user.payment >= 0n ? "pays" : "receives";
+        console.log(`  Account ${user.idx} (position ${user.position}) ${verb} ${user.payment < 0n ? -user.payment : user.payment}`);
+      }
+    });
+}
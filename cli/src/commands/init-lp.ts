@@ -69,6 +69,6 @@ export function registerInitLp(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
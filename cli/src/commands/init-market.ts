@@ -11,6 +11,7 @@ import {
   WELL_KNOWN,
 } from "../abi/accounts.js";
 import { buildIx, simulateOrSend, formatResult } from "../runtime/tx.js";
+import { resolveMarketParams, MARKET_TEMPLATES } from "../runtime/templates.js";
 
 export function registerInitMarket(program: Command): void {
   program
@@ -20,23 +21,27 @@ export function registerInitMarket(program: Command): void {
     .requiredOption("--mint <pubkey>", "Collateral token mint")
     .requiredOption("--vault <pubkey>", "Collateral vault token account")
     .requiredOption("--index-feed-id <hex>", "Pyth index feed ID (64 hex chars, no 0x)")
-    .requiredOption("--max-staleness-secs <string>", "Max oracle staleness (seconds)")
-    .requiredOption("--conf-filter-bps <number>", "Oracle confidence filter (bps)")
+    .option(
+      "--template <name>",
+      `Fill unset parameters from a template (${Object.keys(MARKET_TEMPLATES).join(", ")})`
+    )
+    .option("--max-staleness-secs <string>", "Max oracle staleness (seconds)")
+    .option("--conf-filter-bps <number>", "Oracle confidence filter (bps)")
     .option("--invert <number>", "Invert oracle price (0=no, 1=yes)", "0")
     .option("--unit-scale <number>", "Lamports per unit scale (0=no scaling)", "0")
-    .requiredOption("--warmup-period <string>", "Warmup period (slots)")
-    .requiredOption("--maintenance-margin-bps <string>", "Maintenance margin (bps)")
-    .requiredOption("--initial-margin-bps <string>", "Initial margin (bps)")
-    .requiredOption("--trading-fee-bps <string>", "Trading fee (bps)")
-    .requiredOption("--max-accounts <string>", "Max accounts")
-    .requiredOption("--new-account-fee <string>", "New account fee (u128)")
-    .requiredOption("--risk-reduction-threshold <string>", "Risk reduction threshold (u128)")
-    .requiredOption("--maintenance-fee-per-slot <string>", "Maintenance fee per slot (u128)")
-    .requiredOption("--max-crank-staleness <string>", "Max crank staleness (slots)")
-    .requiredOption("--liquidation-fee-bps <string>", "Liquidation fee (bps)")
-    .requiredOption("--liquidation-fee-cap <string>", "Liquidation fee cap (u128)")
-    .requiredOption("--liquidation-buffer-bps <string>", "Liquidation buffer (bps)")
-    .requiredOption("--min-liquidation-abs <string>", "Min liquidation absolute (u128)")
+    .option("--warmup-period <string>", "Warmup period (slots)")
+    .option("--maintenance-margin-bps <string>", "Maintenance margin (bps)")
+    .option("--initial-margin-bps <string>", "Initial margin (bps)")
+    .option("--trading-fee-bps <string>", "Trading fee (bps)")
+    .option("--max-accounts <string>", "Max accounts")
+    .option("--new-account-fee <string>", "New account fee (u128)")
+    .option("--risk-reduction-threshold <string>", "Risk reduction threshold (u128)")
+    .option("--maintenance-fee-per-slot <string>", "Maintenance fee per slot (u128)")
+    .option("--max-crank-staleness <string>", "Max crank staleness (slots)")
+    .option("--liquidation-fee-bps <string>", "Liquidation fee (bps)")
+    .option("--liquidation-fee-cap <string>", "Liquidation fee cap (u128)")
+    .option("--liquidation-buffer-bps <string>", "Liquidation buffer (bps)")
+    .option("--min-liquidation-abs <string>", "Min liquidation absolute (u128)")
     .action(async (opts, cmd) => {
       const flags = getGlobalFlags(cmd);
       const config = loadConfig(flags);
@@ -53,18 +58,9 @@ export function registerInitMarket(program: Command): void {
         throw new Error("Invalid feed ID: must be 64 hex characters");
       }
 
-      // Derive vault authority for dummy ATA lookup (unused but required)
-      const [vaultPda] = deriveVaultAuthority(ctx.programId, slabPk);
-
-      // Build instruction data
-      const ixData = encodeInitMarket({
-        admin: ctx.payer.publicKey,
-        collateralMint: mint,
-        indexFeedId: feedIdHex,
+      const params = resolveMarketParams(opts.template, {
         maxStalenessSecs: opts.maxStalenessSecs,
-        confFilterBps: parseInt(opts.confFilterBps, 10),
-        invert: parseInt(opts.invert, 10),
-        unitScale: parseInt(opts.unitScale, 10),
+        confFilterBps: opts.confFilterBps,
         warmupPeriodSlots: opts.warmupPeriod,
         maintenanceMarginBps: opts.maintenanceMarginBps,
         initialMarginBps: opts.initialMarginBps,
@@ -80,6 +76,20 @@ export function registerInitMarket(program: Command): void {
         minLiquidationAbs: opts.minLiquidationAbs,
       });
 
+      // Derive vault authority for dummy ATA lookup (unused but required)
+      const [vaultPda] = deriveVaultAuthority(ctx.programId, slabPk);
+
+      // Build instruction data
+      const ixData = encodeInitMarket({
+        admin: ctx.payer.publicKey,
+        collateralMint: mint,
+        indexFeedId: feedIdHex,
+        invert: parseInt(opts.invert, 10),
+        unitScale: parseInt(opts.unitScale, 10),
+        ...params,
+        confFilterBps: parseInt(params.confFilterBps, 10),
+      });
+
       // Build account metas (order matches ACCOUNTS_INIT_MARKET)
       const keys = buildAccountMetas(ACCOUNTS_INIT_MARKET, [
         ctx.payer.publicKey, // admin
@@ -107,6 +117,6 @@ export function registerInitMarket(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
@@ -76,6 +76,6 @@ export function registerKeeperCrank(program: Command): void {
         computeUnitLimit,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
@@ -0,0 +1,142 @@
+import { Command } from "commander";
+import { PublicKey } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import {
+  fetchSlab,
+  parseConfig,
+  parseParams,
+  parseUsedIndices,
+  parseAccount,
+  isAccountUsed,
+  AccountKind,
+} from "../solana/slab.js";
+import { fetchEnginePriceE6 } from "../solana/oracle.js";
+import { quoteFill, PASSIVE_MATCHER_EDGE_BPS, FillQuote } from "../runtime/quote.js";
+import { previewFill } from "../runtime/margin.js";
+import {
+  validatePublicKey,
+  validateIndex,
+  validateI128,
+  validateBps,
+  ValidationError,
+} from "../validation.js";
+
+export function registerQuote(program: Command): void {
+  program
+    .command("quote")
+    .description("Estimate fill price, slippage and fees for a trade without sending it")
+    .requiredOption("--slab <pubkey>", "Slab account public key")
+    .requiredOption("--size <string>", "Trade size (i128, positive=long, negative=short)")
+    .option("--oracle <pubkey>", "Price oracle account (default: market index feed)")
+    .option("--lp-idx <number>", "Quote against this LP (default: best LP)")
+    .option("--user-idx <number>", "Also preview post-trade margin for this user")
+    .option("--edge-bps <number>", "Matcher edge in bps", PASSIVE_MATCHER_EDGE_BPS.toString())
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabPk = validatePublicKey(opts.slab, "--slab");
+      const size = validateI128(opts.size, "--size");
+      if (size === 0n) {
+        throw new ValidationError("--size", "must be non-zero");
+      }
+      const edgeBps = BigInt(validateBps(opts.edgeBps, "--edge-bps"));
+
+      const data = await fetchSlab(ctx.connection, slabPk);
+      const mktConfig = parseConfig(data);
+      const params = parseParams(data);
+      const oracle: PublicKey = opts.oracle
+        ? validatePublicKey(opts.oracle, "--oracle")
+        : mktConfig.indexFeedId;
+      const oraclePrice = await fetchEnginePriceE6(
+        ctx.connection,
+        oracle,
+        mktConfig.invert,
+        mktConfig.unitScale
+      );
+
+      // Candidate LPs: the requested one, or every LP in the slab
+      let lpIndices: number[];
+      if (opts.lpIdx !== undefined) {
+        const idx = validateIndex(opts.lpIdx, "--lp-idx");
+        if (!isAccountUsed(data, idx) || parseAccount(data, idx).kind !== AccountKind.LP) {
+          throw new ValidationError("--lp-idx", `account ${idx} is not an LP in this market`);
+        }
+        lpIndices = [idx];
+      } else {
+        lpIndices = parseUsedIndices(data).filter(
+          (idx) => parseAccount(data, idx).kind === AccountKind.LP
+        );
+      }
+      if (lpIndices.length === 0) {
+        throw new Error("No LPs found in this market");
+      }
+
+      // All passive LPs quote the same price; prefer the one with most capital
+      const q: FillQuote = quoteFill(oraclePrice, size, edgeBps, params.tradingFeeBps);
+      const lpIdx = lpIndices.reduce((best, idx) =>
+        parseAccount(data, idx).capital > parseAccount(data, best).capital ? idx : best
+      );
+
+      const margin =
+        opts.userIdx !== undefined
+          ? previewFill(
+              parseAccount(data, validateIndex(opts.userIdx, "--user-idx")),
+              params,
+              size,
+              q.execPrice,
+              oraclePrice
+            )
+          : null;
+
+      if (flags.json) {
+        console.log(
+          JSON.stringify(
+            {
+              lpIdx,
+              side: q.side,
+              size: q.size.toString(),
+              oraclePriceE6: oraclePrice.toString(),
+              execPriceE6: q.execPrice.toString(),
+              effectivePriceE6: q.effectivePrice.toString(),
+              slippageBps: q.slippageBps,
+              notional: q.notional.toString(),
+              fee: q.fee.toString(),
+              margin: margin && {
+                newPosition: margin.newPosition.toString(),
+                equity: margin.equity.toString(),
+                maintenanceRequired: margin.maintenanceRequired.toString(),
+                initialRequired: margin.initialRequired.toString(),
+                freeCollateral: margin.freeCollateral.toString(),
+                belowMaintenance: margin.belowMaintenance,
+                belowInitial: margin.belowInitial,
+              },
+            },
+            null,
+            2
+          )
+        );
+        return;
+      }
+
+      console.log(`Quote: ${q.side} ${q.size < 0n ? -q.size : q.size} via LP ${lpIdx} (${edgeBps}bps matcher)`);
+      console.log(`  Oracle (mid):     ${oraclePrice}`);
+      console.log(`  Exec price:       ${q.execPrice}`);
+      console.log(`  Slippage vs mid:  ${q.slippageBps.toFixed(2)} bps`);
+      console.log(`  Notional:         ${q.notional}`);
+      console.log(`  Fee:              ${q.fee} (${params.tradingFeeBps} bps)`);
+      console.log(`  Effective price:  ${q.effectivePrice}`);
+      if (margin) {
+        console.log(`  Post-trade equity ${margin.equity}, maintenance ${margin.maintenanceRequired}, ` +
+          `initial ${margin.initialRequired}`);
+        if (margin.belowMaintenance) {
+          console.log("  Program would reject: below maintenance margin");
+        } else if (margin.belowInitial) {
+          console.log("  Warning: below initial margin after fill");
+        }
+      }
+    });
+}
@@ -0,0 +1,51 @@
+import { Command } from "commander";
+import { LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import {
+  checkRent,
+  formatRentCheck,
+  SLAB_SIZE,
+  MATCHER_CTX_SIZE,
+} from "../runtime/rent.js";
+import { validateU64 } from "../validation.js";
+
+export function registerRentCheck(program: Command): void {
+  program
+    .command("rent-check")
+    .description("Show rent needed for new market accounts and check the payer can cover it")
+    .option("--space <bytes>", "Check a custom account size instead of slab + matcher context")
+    .option("--airdrop", "Request the shortfall via airdrop (devnet/localnet only)")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const accounts = opts.space
+        ? [{ label: "account", space: Number(validateU64(opts.space, "--space")) }]
+        : [
+            { label: "slab", space: SLAB_SIZE },
+            { label: "matcher context", space: MATCHER_CTX_SIZE },
+          ];
+
+      let check = await checkRent(ctx.connection, ctx.payer.publicKey, accounts);
+
+      if (check.shortfall > 0 && opts.airdrop) {
+        const sol = Math.ceil(check.shortfall / LAMPORTS_PER_SOL);
+        const sig = await ctx.connection.requestAirdrop(ctx.payer.publicKey, sol * LAMPORTS_PER_SOL);
+        await ctx.connection.confirmTransaction(sig, ctx.commitment);
+        check = await checkRent(ctx.connection, ctx.payer.publicKey, accounts);
+      }
+
+      if (flags.json) {
+        console.log(JSON.stringify(check, null, 2));
+      } else {
+        console.log(`Rent check for ${ctx.payer.publicKey.toBase58()}:`);
+        console.log(formatRentCheck(check, ctx.payer.publicKey));
+      }
+      if (check.shortfall > 0) {
+        process.exitCode = 1;
+      }
+    });
+}
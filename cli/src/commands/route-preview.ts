@@ -0,0 +1,178 @@
+import { Command } from "commander";
+import { PublicKey } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import {
+  fetchSlab,
+  parseConfig,
+  parseParams,
+  parseAllAccounts,
+  AccountKind,
+} from "../solana/slab.js";
+import { fetchEnginePriceE6 } from "../solana/oracle.js";
+import { computePassiveQuote, PASSIVE_MATCHER_EDGE_BPS } from "../runtime/quote.js";
+import { lpCapacity, planRoute, RouteCandidate } from "../runtime/route.js";
+import { previewFill, FillPreview } from "../runtime/margin.js";
+import {
+  validatePublicKey,
+  validateI128,
+  validateBps,
+  ValidationError,
+} from "../validation.js";
+
+// PERCOLAT magic bytes
+const PERCOLAT_MAGIC = Buffer.from([0x50, 0x45, 0x52, 0x43, 0x4f, 0x4c, 0x41, 0x54]);
+
+interface Venue {
+  slab: PublicKey;
+  data: Buffer;
+  oraclePrice: bigint;
+}
+
+export function registerRoutePreview(program: Command): void {
+  program
+    .command("route-preview")
+    .description("Plan how a trade would split across every market for an instrument")
+    .requiredOption("--size <string>", "Trade size (i128, positive=long, negative=short)")
+    .option("--feed <pubkey>", "Only consider markets with this index feed")
+    .option("--slabs <list>", "Comma-separated slab pubkeys (default: all program markets)")
+    .option("--user <pubkey>", "Owner whose user accounts get a margin preview (default: wallet)")
+    .option("--edge-bps <number>", "Matcher edge in bps", PASSIVE_MATCHER_EDGE_BPS.toString())
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const size = validateI128(opts.size, "--size");
+      if (size === 0n) {
+        throw new ValidationError("--size", "must be non-zero");
+      }
+      const edgeBps = BigInt(validateBps(opts.edgeBps, "--edge-bps"));
+      const feed = opts.feed ? validatePublicKey(opts.feed, "--feed") : null;
+      const owner = opts.user ? validatePublicKey(opts.user, "--user") : ctx.payer.publicKey;
+
+      // Gather candidate slabs
+      let slabs: { pubkey: PublicKey; data: Buffer }[];
+      if (opts.slabs) {
+        const keys = (opts.slabs as string)
+          .split(",")
+          .map((s) => validatePublicKey(s.trim(), "--slabs"));
+        slabs = await Promise.all(
+          keys.map(async (pubkey) => ({ pubkey, data: await fetchSlab(ctx.connection, pubkey) }))
+        );
+      } else {
+        const accounts = await ctx.connection.getProgramAccounts(ctx.programId, {
+          filters: [{ memcmp: { offset: 0, bytes: PERCOLAT_MAGIC.toString("base64") } }],
+        });
+        slabs = accounts.map(({ pubkey, account }) => ({ pubkey, data: Buffer.from(account.data) }));
+      }
+      if (feed) {
+        slabs = slabs.filter(({ data }) => parseConfig(data).indexFeedId.equals(feed));
+      }
+
+      // Price every venue in engine space
+      const venues: Venue[] = [];
+      for (const { pubkey, data } of slabs) {
+        const mkt = parseConfig(data);
+        try {
+          const oraclePrice = await fetchEnginePriceE6(
+            ctx.connection,
+            mkt.indexFeedId,
+            mkt.invert,
+            mkt.unitScale
+          );
+          venues.push({ slab: pubkey, data, oraclePrice });
+        } catch (e) {
+          console.error(`Skipping ${pubkey.toBase58()}: ${e instanceof Error ? e.message : e}`);
+        }
+      }
+
+      // One candidate per LP, sized by how much it can absorb
+      const takerSide: 1n | -1n = size > 0n ? 1n : -1n;
+      const candidates: RouteCandidate[] = [];
+      for (const v of venues) {
+        const params = parseParams(v.data);
+        const { bid, ask } = computePassiveQuote(v.oraclePrice, edgeBps);
+        for (const { idx, account } of parseAllAccounts(v.data)) {
+          if (account.kind !== AccountKind.LP) continue;
+          candidates.push({
+            slab: v.slab.toBase58(),
+            lpIdx: idx,
+            execPrice: takerSide > 0n ? ask : bid,
+            capacity: lpCapacity(account, params.maintenanceMarginBps, v.oraclePrice, takerSide),
+          });
+        }
+      }
+      if (candidates.length === 0) {
+        throw new Error("No LPs found in the selected markets");
+      }
+
+      const plan = planRoute(candidates, size);
+
+      // Margin impact per leg for the owner's user account on that slab
+      const margins = plan.legs.map((leg) => {
+        const v = venues.find((x) => x.slab.toBase58() === leg.slab)!;
+        const user = parseAllAccounts(v.data).find(
+          ({ account }) => account.kind === AccountKind.User && account.owner.equals(owner)
+        );
+        if (!user) return null;
+        return {
+          userIdx: user.idx,
+          preview: previewFill(user.account, parseParams(v.data), leg.size, leg.execPrice, v.oraclePrice),
+        };
+      });
+
+      if (flags.json) {
+        console.log(
+          JSON.stringify(
+            {
+              size: size.toString(),
+              filled: plan.filled.toString(),
+              unfilled: plan.unfilled.toString(),
+              vwapE6: plan.vwap.toString(),
+              legs: plan.legs.map((leg, i) => ({
+                slab: leg.slab,
+                lpIdx: leg.lpIdx,
+                size: leg.size.toString(),
+                execPriceE6: leg.execPrice.toString(),
+                user: margins[i] && {
+                  userIdx: margins[i]!.userIdx,
+                  equity: margins[i]!.preview.equity.toString(),
+                  freeCollateral: margins[i]!.preview.freeCollateral.toString(),
+                  belowMaintenance: margins[i]!.preview.belowMaintenance,
+                  belowInitial: margins[i]!.preview.belowInitial,
+                },
+              })),
+            },
+            null,
+            2
+          )
+        );
+        return;
+      }
+
+      console.log(`Route preview: ${takerSide > 0n ? "buy" : "sell"} ${plan.filled + plan.unfilled} ` +
+        `across ${venues.length} market(s), ${candidates.length} LP(s)\n`);
+      for (const [i, leg] of plan.legs.entries()) {
+        console.log(`  ${leg.slab} LP ${leg.lpIdx}: size ${leg.size} @ ${leg.execPrice}`);
+        const m = margins[i];
+        if (m) {
+          console.log(`    user ${m.userIdx}: ${describeMargin(m.preview)}`);
+        } else {
+          console.log(`    no user account for ${owner.toBase58()} on this market`);
+        }
+      }
+      console.log(`\nExpected VWAP: ${plan.vwap}`);
+      console.log(`Filled: ${plan.filled}  Unfilled: ${plan.unfilled}`);
+    });
+}
+
+function describeMargin(p: FillPreview): string {
+  const status = p.belowMaintenance
+    ? "REJECT (below maintenance)"
+    : p.belowInitial
+      ? "warning (below initial)"
+      : "ok";
+  return `equity ${p.equity}, initial ${p.initialRequired}, free ${p.freeCollateral} - ${status}`;
+}
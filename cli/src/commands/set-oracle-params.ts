@@ -0,0 +1,56 @@
+import { Command } from "commander";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { encodeSetOracleParams } from "../abi/instructions.js";
+import {
+  ACCOUNTS_SET_ORACLE_PARAMS,
+  buildAccountMetas,
+} from "../abi/accounts.js";
+import { buildIx, simulateOrSend, formatResult } from "../runtime/tx.js";
+import { validatePublicKey, validateU64, validateBps, ValidationError } from "../validation.js";
+
+export function registerSetOracleParams(program: Command): void {
+  program
+    .command("set-oracle-params")
+    .description("Update oracle staleness and confidence limits (admin only)")
+    .requiredOption("--slab <pubkey>", "Slab account public key")
+    .requiredOption("--max-staleness-secs <string>", "Max oracle price age (seconds)")
+    .requiredOption("--conf-filter-bps <number>", "Max oracle confidence interval (bps of price)")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabPk = validatePublicKey(opts.slab, "--slab");
+      const maxStalenessSecs = validateU64(opts.maxStalenessSecs, "--max-staleness-secs");
+      if (maxStalenessSecs === 0n) {
+        throw new ValidationError("--max-staleness-secs", "must be non-zero");
+      }
+      const confFilterBps = validateBps(opts.confFilterBps, "--conf-filter-bps");
+
+      const ixData = encodeSetOracleParams({ maxStalenessSecs, confFilterBps });
+
+      // Build account metas (order matches ACCOUNTS_SET_ORACLE_PARAMS)
+      const keys = buildAccountMetas(ACCOUNTS_SET_ORACLE_PARAMS, [
+        ctx.payer.publicKey, // admin
+        slabPk, // slab
+      ]);
+
+      const ix = buildIx({
+        programId: ctx.programId,
+        keys,
+        data: ixData,
+      });
+
+      const result = await simulateOrSend({
+        connection: ctx.connection,
+        ix,
+        signers: [ctx.payer],
+        simulate: flags.simulate ?? false,
+        commitment: ctx.commitment,
+      });
+
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
+    });
+}
@@ -0,0 +1,49 @@
+import { Command } from "commander";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { encodeSetReduceOnly } from "../abi/instructions.js";
+import {
+  ACCOUNTS_SET_REDUCE_ONLY,
+  buildAccountMetas,
+} from "../abi/accounts.js";
+import { buildIx, simulateOrSend, formatResult } from "../runtime/tx.js";
+import { validatePublicKey } from "../validation.js";
+
+export function registerSetReduceOnly(program: Command): void {
+  program
+    .command("set-reduce-only")
+    .description("Put a market into (or out of) reduce-only mode (admin only)")
+    .requiredOption("--slab <pubkey>", "Slab account public key")
+    .option("--off", "Clear reduce-only mode instead of setting it")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabPk = validatePublicKey(opts.slab, "--slab");
+      const ixData = encodeSetReduceOnly({ enabled: !opts.off });
+
+      // Build account metas (order matches ACCOUNTS_SET_REDUCE_ONLY)
+      const keys = buildAccountMetas(ACCOUNTS_SET_REDUCE_ONLY, [
+        ctx.payer.publicKey, // admin
+        slabPk, // slab
+      ]);
+
+      const ix = buildIx({
+        programId: ctx.programId,
+        keys,
+        data: ixData,
+      });
+
+      const result = await simulateOrSend({
+        connection: ctx.connection,
+        ix,
+        signers: [ctx.payer],
+        simulate: flags.simulate ?? false,
+        commitment: ctx.commitment,
+      });
+
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
+    });
+}
@@ -27,6 +27,7 @@ export function registerSlabGet(program: Command): void {
           magic: header.magic.toString(16),
           version: header.version,
           bump: header.bump,
+          reduceOnly: header.reduceOnly,
           admin: header.admin.toBase58(),
           nonce: header.nonce.toString(),
           lastThrUpdateSlot: header.lastThrUpdateSlot.toString(),
@@ -52,6 +53,7 @@ export function registerSlabGet(program: Command): void {
         console.log(`Magic:              0x${output.header.magic}`);
         console.log(`Version:            ${output.header.version}`);
         console.log(`Bump:               ${output.header.bump}`);
+        console.log(`Reduce-only:        ${output.header.reduceOnly}`);
         console.log(`Admin:              ${output.header.admin}`);
         console.log(`Nonce:              ${output.header.nonce}`);
         console.log(`Last Thr Update:    ${output.header.lastThrUpdateSlot}`);
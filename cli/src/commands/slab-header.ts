@@ -26,6 +26,7 @@ export function registerSlabHeader(program: Command): void {
               magic: header.magic.toString(16),
               version: header.version,
               bump: header.bump,
+              reduceOnly: header.reduceOnly,
               admin: header.admin.toBase58(),
               nonce: header.nonce.toString(),
               lastThrUpdateSlot: header.lastThrUpdateSlot.toString(),
@@ -38,6 +39,7 @@ export function registerSlabHeader(program: Command): void {
         console.log(`Magic:              0x${header.magic.toString(16)}`);
         console.log(`Version:            ${header.version}`);
         console.log(`Bump:               ${header.bump}`);
+        console.log(`Reduce-only:        ${header.reduceOnly}`);
         console.log(`Admin:              ${header.admin.toBase58()}`);
         console.log(`Nonce:              ${header.nonce}`);
         console.log(`Last Thr Update:    ${header.lastThrUpdateSlot}`);
@@ -0,0 +1,138 @@
+import { Command } from "commander";
+import { PublicKey } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { fetchSlab, parseConfig, parseEngine, parseParams, parseAllAccounts } from "../solana/slab.js";
+import { fetchEnginePriceE6 } from "../solana/oracle.js";
+import {
+  parseShockBps,
+  shockPrice,
+  stressAccount,
+  summarizeStress,
+  sweepStart,
+  StressResult,
+} from "../runtime/stress.js";
+import { validatePublicKey, ValidationError } from "../validation.js";
+
+export function registerStress(program: Command): void {
+  program
+    .command("stress")
+    .description("Re-mark accounts under price shocks and report equity, shortfall and liquidations")
+    .requiredOption("--slabs <list>", "Comma-separated slab pubkeys")
+    .requiredOption(
+      "--shock <list>",
+      "Shock for every market (e.g. -20%), and/or per market as <slab>=<shock>, comma-separated"
+    )
+    .option("--owner <pubkey>", "Only stress accounts of this owner (default: wallet)")
+    .option("--all", "Stress every account in the slabs, not just the owner's")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const slabs = (opts.slabs as string)
+        .split(",")
+        .map((s) => validatePublicKey(s.trim(), "--slabs"));
+      const owner: PublicKey | null = opts.all
+        ? null
+        : opts.owner
+          ? validatePublicKey(opts.owner, "--owner")
+          : ctx.payer.publicKey;
+
+      // A bare shock applies to every market (correlated move); <slab>=<shock> overrides one
+      let defaultShock: bigint | null = null;
+      const perSlab = new Map<string, bigint>();
+      for (const entry of (opts.shock as string).split(",")) {
+        const [key, value] = entry.includes("=") ? entry.split("=") : [null, entry];
+        let bps: bigint;
+        try {
+          bps = parseShockBps(value);
+        } catch (e) {
+          throw new ValidationError("--shock", e instanceof Error ? e.message : String(e));
+        }
+        if (key === null) {
+          defaultShock = bps;
+        } else {
+          perSlab.set(validatePublicKey(key.trim(), "--shock").toBase58(), bps);
+        }
+      }
+
+      const results: StressResult[] = [];
+      const sweepStarts = new Map<string, number>();
+      const markets: { slab: string; shockBps: bigint; priceE6: bigint; shockedE6: bigint }[] = [];
+      for (const pubkey of slabs) {
+        const slab = pubkey.toBase58();
+        const shockBps = perSlab.get(slab) ?? defaultShock ?? 0n;
+        const data = await fetchSlab(ctx.connection, pubkey);
+        const mkt = parseConfig(data);
+        const params = parseParams(data);
+        const price = await fetchEnginePriceE6(ctx.connection, mkt.indexFeedId, mkt.invert, mkt.unitScale);
+        const shocked = shockPrice(price, shockBps);
+        markets.push({ slab, shockBps, priceE6: price, shockedE6: shocked });
+        sweepStarts.set(slab, sweepStart(parseEngine(data).crankStep));
+
+        for (const { idx, account } of parseAllAccounts(data)) {
+          if (owner && !account.owner.equals(owner)) continue;
+          results.push(stressAccount({ ...account, slab, idx }, params, price, shocked));
+        }
+      }
+
+      const report = summarizeStress(results, sweepStarts);
+
+      if (flags.json) {
+        const fmt = (r: StressResult) => ({
+          slab: r.slab,
+          idx: r.idx,
+          positionSize: r.positionSize.toString(),
+          shockedPriceE6: r.shockedPrice.toString(),
+          equityBefore: r.equityBefore.toString(),
+          equity: r.equity.toString(),
+          maintenanceRequired: r.maintenanceRequired.toString(),
+          shortfall: r.shortfall.toString(),
+          liquidatable: r.liquidatable,
+        });
+        console.log(
+          JSON.stringify(
+            {
+              markets: markets.map((m) => ({
+                slab: m.slab,
+                shockBps: m.shockBps.toString(),
+                priceE6: m.priceE6.toString(),
+                shockedPriceE6: m.shockedE6.toString(),
+              })),
+              equityBefore: report.equityBefore.toString(),
+              equityAfter: report.equityAfter.toString(),
+              totalShortfall: report.totalShortfall.toString(),
+              accounts: report.results.map(fmt),
+              liquidationOrder: report.liquidations.map(fmt),
+            },
+            null,
+            2
+          )
+        );
+        return;
+      }
+
+      console.log(`Stress test over ${markets.length} market(s), ${results.length} account(s):\n`);
+      for (const m of markets) {
+        console.log(`  ${m.slab}: ${m.priceE6} -> ${m.shockedE6} (${Number(m.shockBps) / 100}%)`);
+      }
+      console.log("");
+      for (const r of report.results) {
+        const status = r.liquidatable ? "LIQUIDATABLE" : r.positionSize === 0n ? "flat" : "ok";
+        console.log(`  ${r.slab} #${r.idx}: pos ${r.positionSize}, equity ${r.equityBefore} -> ${r.equity}, ` +
+          `maintenance ${r.maintenanceRequired} - ${status}`);
+      }
+      console.log(`\nTotal equity: ${report.equityBefore} -> ${report.equityAfter}`);
+      console.log(`Total maintenance shortfall: ${report.totalShortfall}`);
+      if (report.liquidations.length > 0) {
+        console.log("Liquidation order (crank sweep order):");
+        for (const [i, r] of report.liquidations.entries()) {
+          console.log(`  ${i + 1}. ${r.slab} #${r.idx} (shortfall ${r.shortfall})`);
+        }
+      } else {
+        console.log("No accounts would be liquidated.");
+      }
+    });
+}
@@ -13,6 +13,7 @@ import {
   WELL_KNOWN,
 } from "../abi/accounts.js";
 import { buildIx, simulateOrSend, formatResult } from "../runtime/tx.js";
+import { tradePreflight, reportPreflight } from "../runtime/preflight.js";
 import {
   validatePublicKey,
   validateIndex,
@@ -30,6 +31,7 @@ export function registerTradeCpi(program: Command): void {
     .requiredOption("--matcher-program <pubkey>", "Matcher program ID")
     .requiredOption("--matcher-context <pubkey>", "Matcher context account")
     .option("--lp-wallet <path>", "LP owner wallet keypair (if different from payer)")
+    .option("--no-preflight", "Skip the local post-trade margin check")
     .action(async (opts, cmd) => {
       const flags = getGlobalFlags(cmd);
       const config = loadConfig(flags);
@@ -41,12 +43,21 @@ export function registerTradeCpi(program: Command): void {
       const matcherContext = validatePublicKey(opts.matcherContext, "--matcher-context");
       const lpIdx = validateIndex(opts.lpIdx, "--lp-idx");
       const userIdx = validateIndex(opts.userIdx, "--user-idx");
-      validateI128(opts.size, "--size");
+      const size = validateI128(opts.size, "--size");
 
       // Fetch slab config for oracle
       const data = await fetchSlab(ctx.connection, slabPk);
       const mktConfig = parseConfig(data);
 
+      // Local margin check (estimate: assumes fill at oracle, matcher may differ)
+      if (opts.preflight) {
+        const pf = await tradePreflight(ctx.connection, data, mktConfig.indexFeedId, lpIdx, userIdx, size);
+        if (!reportPreflight(pf)) {
+          process.exitCode = 1;
+          return;
+        }
+      }
+
       // Derive LP PDA
       const [lpPda] = deriveLpPda(ctx.programId, slabPk, lpIdx);
 
@@ -92,6 +103,6 @@ export function registerTradeCpi(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
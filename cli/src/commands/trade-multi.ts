@@ -0,0 +1,126 @@
+import { Command } from "commander";
+import { Keypair, TransactionInstruction } from "@solana/web3.js";
+import { getGlobalFlags } from "../cli.js";
+import { loadConfig } from "../config.js";
+import { createContext } from "../runtime/context.js";
+import { fetchSlab, parseConfig, parseAccount, AccountKind } from "../solana/slab.js";
+import { deriveLpPda } from "../solana/pda.js";
+import { loadKeypair } from "../solana/wallet.js";
+import { encodeTradeCpi } from "../abi/instructions.js";
+import {
+  ACCOUNTS_TRADE_CPI,
+  buildAccountMetas,
+  WELL_KNOWN,
+} from "../abi/accounts.js";
+import { buildIx, simulateOrSend, formatResult, formatFill } from "../runtime/tx.js";
+import { parseLogTree } from "../runtime/logs.js";
+import { validateTradeLeg, TradeLeg, ValidationError } from "../validation.js";
+
+function collect(value: string, previous: string[]): string[] {
+  return [...previous, value];
+}
+
+export function registerTradeMulti(program: Command): void {
+  program
+    .command("trade-multi")
+    .description("Execute several CPI trades atomically in one transaction")
+    .option(
+      "--leg <slab:lpIdx:userIdx:size>",
+      "Trade leg (repeatable, at least one; size positive=long, negative=short)",
+      collect,
+      []
+    )
+    .option("--lp-wallet <path>", "LP owner wallet keypair (repeatable, default: payer)", collect, [])
+    .option("--compute-units <number>", "Compute unit limit for the whole transaction")
+    .action(async (opts, cmd) => {
+      const flags = getGlobalFlags(cmd);
+      const config = loadConfig(flags);
+      const ctx = createContext(config);
+
+      const legs: TradeLeg[] = (opts.leg as string[]).map((l) => validateTradeLeg(l, "--leg"));
+      if (legs.length === 0) {
+        throw new ValidationError("--leg", "at least one leg is required");
+      }
+      const lpOwners: Keypair[] = (opts.lpWallet as string[]).map((p) => loadKeypair(p));
+
+      // Fetch each slab once, even if several legs share it
+      const slabData = new Map<string, Buffer>();
+      for (const leg of legs) {
+        const key = leg.slab.toBase58();
+        if (!slabData.has(key)) {
+          slabData.set(key, await fetchSlab(ctx.connection, leg.slab));
+        }
+      }
+
+      const signers: Keypair[] = [ctx.payer];
+      const ixs: TransactionInstruction[] = legs.map((leg, i) => {
+        const data = slabData.get(leg.slab.toBase58())!;
+        const mktConfig = parseConfig(data);
+        const lp = parseAccount(data, leg.lpIdx);
+        if (lp.kind !== AccountKind.LP) {
+          throw new Error(`Leg ${i + 1}: account ${leg.lpIdx} on ${leg.slab.toBase58()} is not an LP`);
+        }
+
+        // The LP owner must sign; use the payer or one of --lp-wallet
+        const lpOwnerKeypair = [ctx.payer, ...lpOwners].find((k) => k.publicKey.equals(lp.owner));
+        if (!lpOwnerKeypair) {
+          throw new Error(`Leg ${i + 1}: no --lp-wallet for LP owner ${lp.owner.toBase58()}`);
+        }
+        if (!signers.some((s) => s.publicKey.equals(lpOwnerKeypair.publicKey))) {
+          signers.push(lpOwnerKeypair);
+        }
+
+        const [lpPda] = deriveLpPda(ctx.programId, leg.slab, leg.lpIdx);
+
+        // Build account metas (order matches ACCOUNTS_TRADE_CPI)
+        const keys = buildAccountMetas(ACCOUNTS_TRADE_CPI, [
+          ctx.payer.publicKey, // user
+          lpOwnerKeypair.publicKey, // lpOwner
+          leg.slab, // slab
+          WELL_KNOWN.clock, // clock
+          mktConfig.indexFeedId, // oracle (use index feed ID from config)
+          lp.matcherProgram, // matcherProg
+          lp.matcherContext, // matcherCtx
+          lpPda, // lpPda
+        ]);
+
+        return buildIx({
+          programId: ctx.programId,
+          keys,
+          data: encodeTradeCpi({
+            lpIdx: leg.lpIdx,
+            userIdx: leg.userIdx,
+            size: leg.size.toString(),
+          }),
+        });
+      });
+
+      const computeUnitLimit = opts.computeUnits
+        ? parseInt(opts.computeUnits, 10)
+        : undefined;
+
+      const result = await simulateOrSend({
+        connection: ctx.connection,
+        ix: ixs,
+        signers,
+        simulate: flags.simulate ?? false,
+        commitment: ctx.commitment,
+        computeUnitLimit,
+      });
+
+      // Transaction return data only keeps the last leg's fill; each leg's
+      // own result is in its "Program return:" log line
+      const programId = ctx.programId.toBase58();
+      const legReturnData = parseLogTree(result.logs)
+        .filter((node) => node.programId === programId)
+        .map((node) => (node.returnData?.programId === programId ? node.returnData.data : undefined));
+      const summary = { ...result, returnData: undefined, legReturnData };
+
+      console.log(formatResult(summary, flags.json ?? false, flags.verbose));
+      if (!flags.json && !result.err) {
+        legReturnData.forEach((data, i) => {
+          formatFill(data).forEach((line) => console.log(`Leg ${i + 1} ${line}`));
+        });
+      }
+    });
+}
@@ -11,6 +11,8 @@ import {
   WELL_KNOWN,
 } from "../abi/accounts.js";
 import { buildIx, simulateOrSend, formatResult } from "../runtime/tx.js";
+import { tradePreflight, reportPreflight } from "../runtime/preflight.js";
+import { fetchSlab } from "../solana/slab.js";
 import {
   validatePublicKey,
   validateIndex,
@@ -27,6 +29,7 @@ export function registerTradeNocpi(program: Command): void {
     .requiredOption("--size <string>", "Trade size (i128, positive=long, negative=short)")
     .requiredOption("--oracle <pubkey>", "Price oracle account")
     .option("--lp-wallet <path>", "LP wallet keypair (if different from payer)")
+    .option("--no-preflight", "Skip the local post-trade margin check")
     .action(async (opts, cmd) => {
       const flags = getGlobalFlags(cmd);
       const config = loadConfig(flags);
@@ -37,7 +40,17 @@ export function registerTradeNocpi(program: Command): void {
       const oracle = validatePublicKey(opts.oracle, "--oracle");
       const lpIdx = validateIndex(opts.lpIdx, "--lp-idx");
       const userIdx = validateIndex(opts.userIdx, "--user-idx");
-      validateI128(opts.size, "--size");
+      const size = validateI128(opts.size, "--size");
+
+      // Local margin check before paying for a round trip
+      if (opts.preflight) {
+        const slabData = await fetchSlab(ctx.connection, slabPk);
+        const pf = await tradePreflight(ctx.connection, slabData, oracle, lpIdx, userIdx, size);
+        if (!reportPreflight(pf)) {
+          process.exitCode = 1;
+          return;
+        }
+      }
 
       // Load LP keypair if provided, otherwise use payer
       const lpKeypair = opts.lpWallet ? loadKeypair(opts.lpWallet) : ctx.payer;
@@ -78,6 +91,6 @@ export function registerTradeNocpi(program: Command): void {
         commitment: ctx.commitment,
       });
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
@@ -109,6 +109,6 @@ export function registerUpdateConfig(program: Command): void {
         console.log(`  Thresh Min Step:     ${configArgs.threshMinStep}`);
       }
 
-      console.log(formatResult(result, flags.json ?? false));
+      console.log(formatResult(result, flags.json ?? false, flags.verbose));
     });
 }
@@ -22,6 +22,7 @@ export interface GlobalFlags {
   commitment?: Commitment;
   json?: boolean;
   simulate?: boolean;
+  verbose?: boolean;
 }
 
 const DEFAULT_CONFIG_NAME = "percolator-cli.json";
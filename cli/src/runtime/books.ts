@@ -0,0 +1,163 @@
+/**
+ * Off-chain accounting snapshot of one slab. Mirrors the engine's
+ * conservation check: vault + loss_accum must cover every account's
+ * capital and funding-settled PnL plus the insurance fund. The token
+ * vault's real balance is reported next to the engine's vault counter so
+ * an auditor can see both sides of the ledger.
+ *
+ * Engine amounts are in units. With unit_scale > 0 the token vault holds
+ * vault * unit_scale base tokens plus the slab's dust_base remainder.
+ */
+
+import { markPnl } from "./margin.js";
+
+export interface BooksEngine {
+  vault: bigint;
+  insuranceFund: { balance: bigint; feeRevenue: bigint };
+  lossAccum: bigint;
+  fundingIndexQpbE6: bigint;
+}
+
+export interface BooksAccount {
+  capital: bigint;
+  pnl: bigint;
+  positionSize: bigint;
+  entryPrice: bigint;
+  fundingIndex: bigint;
+}
+
+export interface BooksOptions {
+  /** SPL token balance of the vault, in base tokens */
+  tokenVault?: bigint | null;
+  /** MarketConfig unit_scale (0 = engine amounts are base tokens) */
+  unitScale?: number;
+  /** Sub-unit deposits held in the token vault but not in the engine */
+  dustBase?: bigint;
+  /** Engine-space oracle price; enables the unrealized PnL line */
+  oraclePriceE6?: bigint | null;
+}
+
+export interface Books {
+  /** Engine vault counter */
+  vault: bigint;
+  lossAccum: bigint;
+  /** vault + loss_accum */
+  assets: bigint;
+  totalCapital: bigint;
+  /** Sum of PnL after settling pending funding */
+  netPnl: bigint;
+  insurance: bigint;
+  /** Fees collected into insurance (informational, already in insurance) */
+  feeRevenue: bigint;
+  /** capital + net pnl + insurance (floored at 0), as the engine computes it */
+  liabilities: bigint;
+  /** assets - liabilities; negative means the books do not balance */
+  surplus: bigint;
+  /**
+   * Mark-to-oracle PnL of open positions, if a price was given. Not yet
+   * realized into pnl, so it is reported beside the ledger, not in it.
+   */
+  unrealizedPnl: bigint | null;
+  unitScale: number;
+  dustBase: bigint;
+  /** Engine vault in base tokens: vault * unit_scale + dust_base */
+  vaultBase: bigint;
+  /** SPL token balance of the vault, if fetched */
+  tokenVault: bigint | null;
+  /** tokenVault - vaultBase (0 when the counter matches the token account) */
+  tokenVaultDiff: bigint | null;
+  balanced: boolean;
+}
+
+/**
+ * PnL after settling funding the way settle_account_funding would:
+ * payers round up, receivers truncate.
+ */
+export function settledPnl(account: BooksAccount, globalIndex: bigint): bigint {
+  if (account.positionSize === 0n) return account.pnl;
+  const deltaF = globalIndex - account.fundingIndex;
+  if (deltaF === 0n) return account.pnl;
+  const raw = account.positionSize * deltaF;
+  const payment = raw > 0n ? (raw + 999_999n) / 1_000_000n : raw / 1_000_000n;
+  return account.pnl - payment;
+}
+
+export function computeBooks(engine: BooksEngine, accounts: BooksAccount[], opts: BooksOptions = {}): Books {
+  const tokenVault = opts.tokenVault ?? null;
+  const unitScale = opts.unitScale ?? 0;
+  const dustBase = opts.dustBase ?? 0n;
+  const oracle = opts.oraclePriceE6 ?? null;
+
+  let totalCapital = 0n;
+  let netPnl = 0n;
+  let unrealized = 0n;
+  for (const a of accounts) {
+    totalCapital += a.capital;
+    netPnl += settledPnl(a, engine.fundingIndexQpbE6);
+    if (oracle !== null) unrealized += markPnl(a.positionSize, a.entryPrice, oracle);
+  }
+
+  const insurance = engine.insuranceFund.balance;
+  // Same as check_conservation: losses net against capital
+  const gross = totalCapital + insurance + netPnl;
+  const liabilities = gross > 0n ? gross : 0n;
+  const assets = engine.vault + engine.lossAccum;
+  const surplus = assets - liabilities;
+  // Same conversion as units_to_base, plus the dust the engine never saw
+  const vaultBase = unitScale > 0 ? engine.vault * BigInt(unitScale) + dustBase : engine.vault;
+  const tokenVaultDiff = tokenVault === null ? null : tokenVault - vaultBase;
+
+  return {
+    vault: engine.vault,
+    lossAccum: engine.lossAccum,
+    assets,
+    totalCapital,
+    netPnl,
+    insurance,
+    feeRevenue: engine.insuranceFund.feeRevenue,
+    liabilities,
+    surplus,
+    unrealizedPnl: oracle === null ? null : unrealized,
+    unitScale,
+    dustBase,
+    vaultBase,
+    tokenVault,
+    tokenVaultDiff,
+    balanced: surplus >= 0n && (tokenVaultDiff === null || tokenVaultDiff >= 0n),
+  };
+}
+
+/**
+ * Human-readable ledger with imbalances flagged.
+ */
+export function formatBooks(b: Books): string {
+  const lines = [
+    "  Assets",
+    `    Vault (engine):      ${b.vault}`,
+    `    Loss accumulator:    ${b.lossAccum}`,
+    `    Total:               ${b.assets}`,
+    "  Liabilities",
+    `    Account capital:     ${b.totalCapital}`,
+    `    Net PnL (settled):   ${b.netPnl}`,
+    `    Insurance fund:      ${b.insurance} (fees ${b.feeRevenue})`,
+    `    Total:               ${b.liabilities}`,
+    `  Surplus:               ${b.surplus}${b.surplus < 0n ? "  <-- IMBALANCE" : ""}`,
+  ];
+  // check_conservation counts realized PnL only, so mark PnL is a memo
+  // line rather than a liability
+  lines.push(
+    b.unrealizedPnl === null
+      ? "  Unrealized PnL (mark): unavailable (no oracle price); not in the ledger"
+      : `  Unrealized PnL (mark): ${b.unrealizedPnl} (not in the ledger until realized)`
+  );
+  if (b.unitScale > 0) {
+    lines.push(`  Vault in base tokens:  ${b.vaultBase} (x${b.unitScale} + dust ${b.dustBase})`);
+  }
+  if (b.tokenVault !== null) {
+    const diff = b.tokenVaultDiff!;
+    lines.push(`  Token vault balance:   ${b.tokenVault}`);
+    lines.push(`  Token vs engine vault: ${diff}${diff < 0n ? "  <-- IMBALANCE" : ""}`);
+  }
+  lines.push(b.balanced ? "Books balance." : "Books DO NOT balance.");
+  return lines.join("\n");
+}
@@ -0,0 +1,73 @@
+/**
+ * Funding prediction using the same math as the on-chain crank:
+ * compute_inventory_funding_bps_per_slot (percolator-prog) for the rate,
+ * accrue_funding for the index step and settle_account_funding for the
+ * per-account payment (percolator engine).
+ */
+
+import { FundingParams } from "../solana/slab.js";
+
+const abs = (x: bigint) => (x < 0n ? -x : x);
+const clamp = (x: bigint, lo: bigint, hi: bigint) => (x < lo ? lo : x > hi ? hi : x);
+
+/**
+ * Funding rate in bps per slot from LP net inventory. Positive = longs pay.
+ */
+export function inventoryFundingBpsPerSlot(netLpPos: bigint, priceE6: bigint, p: FundingParams): bigint {
+  if (netLpPos === 0n || priceE6 === 0n || p.horizonSlots === 0n) return 0n;
+
+  const notional = (abs(netLpPos) * priceE6) / 1_000_000n;
+  const scale = p.invScaleNotionalE6 > 0n ? p.invScaleNotionalE6 : 1n;
+  let premium = (notional * p.kBps) / scale;
+  if (premium > abs(p.maxPremiumBps)) premium = abs(p.maxPremiumBps);
+
+  const signed = netLpPos > 0n ? premium : -premium;
+  // BigInt division truncates toward zero, like i64 division
+  const perSlot = clamp(signed / p.horizonSlots, -10_000n, 10_000n);
+  return clamp(perSlot, -p.maxBpsPerSlot, p.maxBpsPerSlot);
+}
+
+/**
+ * Funding index step for `dt` slots at `rateBpsPerSlot` (engine accrue_funding).
+ */
+export function fundingIndexDelta(priceE6: bigint, rateBpsPerSlot: bigint, dt: bigint): bigint {
+  return (priceE6 * rateBpsPerSlot * dt) / 10_000n;
+}
+
+/**
+ * Funding an account pays (positive) or receives (negative) when it
+ * settles against `globalIndex`. Payments round up, receipts truncate.
+ */
+export function fundingPayment(position: bigint, accountIndex: bigint, globalIndex: bigint): bigint {
+  const raw = position * (globalIndex - accountIndex);
+  if (raw > 0n) return (raw + 999_999n) / 1_000_000n;
+  return raw / 1_000_000n;
+}
+
+export interface FundingPrediction {
+  rateBpsPerSlot: bigint;
+  /** Slots the next crank will accrue (target slot - last funding slot) */
+  dt: bigint;
+  indexDelta: bigint;
+  /** Global funding index after the next crank */
+  nextIndex: bigint;
+}
+
+/**
+ * Predict what a crank landing at `targetSlot` will do to the funding index.
+ * The crank applies the rate it computes at crank time over the whole gap
+ * since the last accrual.
+ */
+export function predictFunding(
+  netLpPos: bigint,
+  priceE6: bigint,
+  params: FundingParams,
+  fundingIndex: bigint,
+  lastFundingSlot: bigint,
+  targetSlot: bigint
+): FundingPrediction {
+  const rateBpsPerSlot = inventoryFundingBpsPerSlot(netLpPos, priceE6, params);
+  const dt = targetSlot > lastFundingSlot ? targetSlot - lastFundingSlot : 0n;
+  const indexDelta = fundingIndexDelta(priceE6, rateBpsPerSlot, dt);
+  return { rateBpsPerSlot, dt, indexDelta, nextIndex: fundingIndex + indexDelta };
+}
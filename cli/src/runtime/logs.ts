@@ -0,0 +1,224 @@
+/**
+ * Transaction log parsing: rebuild the CPI invocation tree from runtime
+ * log lines and extract program events.
+ */
+
+export interface InvocationNode {
+  programId: string;
+  depth: number;
+  status: "success" | "failed" | "incomplete";
+  /** Failure reason from "Program X failed: <reason>" */
+  error?: string;
+  unitsConsumed?: number;
+  /** "Program log:" lines emitted directly by this invocation */
+  logs: string[];
+  /**
+   * "Program return:" data as this invocation finished. programId names the
+   * program that set it, which may be a callee if the caller set none.
+   */
+  returnData?: { programId: string; data: string };
+  events: ProgramEvent[];
+  children: InvocationNode[];
+}
+
+export interface ProgramEvent {
+  name: string;
+  fields: Record<string, string>;
+}
+
+/**
+ * sol_log_64 events emitted by the program, keyed by their first (tag) value.
+ * Field order matches the remaining four sol_log_64 arguments; fields listed
+ * in `signed` are i64 values logged as raw u64 bits.
+ */
+export const EVENT_TAGS: Record<string, { name: string; fields: string[]; signed?: string[] }> = {
+  "0xc8a4c": {
+    name: "CrankStats",
+    fields: ["liquidations", "forceRealizes", "maxAccounts", "insuranceLow"],
+  },
+  // Emitted when a crank completes a full sweep; values are totals since the
+  // previous completion, saturated (not wrapped) to fit one log word
+  "0x5ee9": {
+    name: "SweepSummary",
+    fields: ["feeRevenue", "fundingIndexDelta", "volume", "liquidations"],
+    signed: ["fundingIndexDelta"],
+  },
+};
+
+const INVOKE_RE = /^Program (\w+) invoke \[(\d+)\]$/;
+const SUCCESS_RE = /^Program (\w+) success$/;
+const FAILED_RE = /^Program (\w+) failed: (.*)$/;
+const CONSUMED_RE = /^Program (\w+) consumed (\d+) of (\d+) compute units$/;
+const LOG_RE = /^Program log: (.*)$/;
+const RETURN_RE = /^Program return: (\w+) (\S*)$/;
+const LOG64_RE = /^Program log: (0x[0-9a-f]+), (0x[0-9a-f]+), (0x[0-9a-f]+), (0x[0-9a-f]+), (0x[0-9a-f]+)$/;
+
+function parseEvent(line: string): ProgramEvent | null {
+  const m = line.match(LOG64_RE);
+  if (!m) return null;
+  const known = EVENT_TAGS[m[1]];
+  if (!known) return null;
+  const fields: Record<string, string> = {};
+  known.fields.forEach((f, i) => {
+    const v = BigInt(m[i + 2]);
+    fields[f] = (known.signed?.includes(f) ? BigInt.asIntN(64, v) : v).toString();
+  });
+  return { name: known.name, fields };
+}
+
+/**
+ * Rebuild the invocation tree from transaction log messages.
+ * Returns one root node per top-level instruction.
+ */
+export function parseLogTree(logs: string[]): InvocationNode[] {
+  const roots: InvocationNode[] = [];
+  const stack: InvocationNode[] = [];
+
+  for (const line of logs) {
+    const invoke = line.match(INVOKE_RE);
+    if (invoke) {
+      const node: InvocationNode = {
+        programId: invoke[1],
+        depth: parseInt(invoke[2], 10),
+        status: "incomplete",
+        logs: [],
+        events: [],
+        children: [],
+      };
+      if (stack.length === 0) {
+        roots.push(node);
+      } else {
+        stack[stack.length - 1].children.push(node);
+      }
+      stack.push(node);
+      continue;
+    }
+
+    const top = stack[stack.length - 1];
+    if (!top) continue;
+
+    const consumed = line.match(CONSUMED_RE);
+    if (consumed) {
+      top.unitsConsumed = parseInt(consumed[2], 10);
+      continue;
+    }
+
+    if (SUCCESS_RE.test(line)) {
+      top.status = "success";
+      stack.pop();
+      continue;
+    }
+
+    const failed = line.match(FAILED_RE);
+    if (failed) {
+      top.status = "failed";
+      top.error = failed[2];
+      stack.pop();
+      continue;
+    }
+
+    const ret = line.match(RETURN_RE);
+    if (ret) {
+      top.returnData = { programId: ret[1], data: ret[2] };
+      continue;
+    }
+
+    const log = line.match(LOG_RE);
+    if (log) {
+      top.logs.push(log[1]);
+      const event = parseEvent(line);
+      if (event) top.events.push(event);
+    }
+  }
+
+  return roots;
+}
+
+/**
+ * Find the deepest failed invocation (the one that actually raised the error).
+ */
+export function findFailure(nodes: InvocationNode[]): InvocationNode | null {
+  for (const node of nodes) {
+    const inner = findFailure(node.children);
+    if (inner) return inner;
+    if (node.status === "failed") return node;
+  }
+  return null;
+}
+
+/**
+ * Position of the failing CPI among a top-level invocation's inner
+ * instructions, which the runtime records in invocation order. Null if no
+ * CPI failed (the top-level program itself raised the error).
+ */
+export function failedInnerIndex(root: InvocationNode): number | null {
+  const failure = findFailure(root.children);
+  if (!failure) return null;
+  const flat: InvocationNode[] = [];
+  const walk = (node: InvocationNode) => {
+    flat.push(node);
+    node.children.forEach(walk);
+  };
+  root.children.forEach(walk);
+  return flat.indexOf(failure);
+}
+
+/**
+ * Pretty-print an invocation tree, one line per invocation plus its logs.
+ */
+export function formatLogTree(
+  nodes: InvocationNode[],
+  label: (programId: string) => string = (p) => p,
+  describeError: (error: string, programId: string) => string = (e) => e
+): string[] {
+  const lines: string[] = [];
+  const walk = (node: InvocationNode, indent: string) => {
+    const cu = node.unitsConsumed !== undefined ? ` (${node.unitsConsumed.toLocaleString()} CU)` : "";
+    const status =
+      node.status === "failed"
+        ? ` FAILED: ${describeError(node.error ?? "", node.programId)}`
+        : node.status === "incomplete" ? " (incomplete)" : "";
+    lines.push(`${indent}${label(node.programId)}${cu}${status}`);
+    for (const log of node.logs) {
+      lines.push(`${indent}  | ${log}`);
+    }
+    for (const ev of node.events) {
+      const fields = Object.entries(ev.fields)
+        .map(([k, v]) => `${k}=${v}`)
+        .join(" ");
+      lines.push(`${indent}  * ${ev.name} ${fields}`);
+    }
+    for (const child of node.children) {
+      walk(child, indent + "  ");
+    }
+  };
+  nodes.forEach((n) => walk(n, ""));
+  return lines;
+}
+
+export interface ProgramCu {
+  programId: string;
+  invocations: number;
+  /** CU spent in the program itself, excluding its CPIs */
+  selfUnits: number;
+}
+
+/**
+ * Per-program compute breakdown. "consumed" lines are inclusive of nested
+ * CPIs, so children are subtracted to attribute CU to the program that
+ * actually spent it. Ordered by selfUnits, highest first.
+ */
+export function summarizeCu(nodes: InvocationNode[]): ProgramCu[] {
+  const totals = new Map<string, ProgramCu>();
+  const walk = (node: InvocationNode) => {
+    const childUnits = node.children.reduce((sum, c) => sum + (c.unitsConsumed ?? 0), 0);
+    const self = Math.max(0, (node.unitsConsumed ?? 0) - childUnits);
+    const entry = totals.get(node.programId) ?? { programId: node.programId, invocations: 0, selfUnits: 0 };
+    entry.invocations += 1;
+    entry.selfUnits += self;
+    totals.set(node.programId, entry);
+    node.children.forEach(walk);
+  };
+  nodes.forEach(walk);
+  return [...totals.values()].sort((a, b) => b.selfUnits - a.selfUnits);
+}
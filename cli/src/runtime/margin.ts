@@ -0,0 +1,125 @@
+/**
+ * Off-chain mirror of the engine's margin math (src/percolator.rs).
+ * Used for pre-trade preflight and estimates; the program remains the
+ * authority. Funding / maintenance fees accrued since the last touch are
+ * not applied, so results are estimates around the current on-chain state.
+ */
+
+export interface MarginAccount {
+  capital: bigint;
+  pnl: bigint;
+  positionSize: bigint;
+  entryPrice: bigint;
+}
+
+export interface MarginParams {
+  maintenanceMarginBps: bigint;
+  initialMarginBps: bigint;
+  tradingFeeBps: bigint;
+}
+
+const abs = (x: bigint) => (x < 0n ? -x : x);
+
+/**
+ * mark_pnl_for_position: (oracle - entry) * |pos| / 1e6, mirrored for shorts.
+ * BigInt division truncates toward zero like Rust's i128 division.
+ */
+export function markPnl(pos: bigint, entry: bigint, oracle: bigint): bigint {
+  if (pos === 0n) return 0n;
+  const diff = pos > 0n ? oracle - entry : entry - oracle;
+  return (diff * abs(pos)) / 1_000_000n;
+}
+
+/**
+ * account_equity_mtm_at_oracle: max(0, capital + pnl + mark_pnl)
+ */
+export function equityMtm(a: MarginAccount, oracle: bigint): bigint {
+  const eq = a.capital + a.pnl + markPnl(a.positionSize, a.entryPrice, oracle);
+  return eq > 0n ? eq : 0n;
+}
+
+/**
+ * Margin required for a position at `bps`: |pos| * oracle / 1e6 * bps / 10_000
+ */
+export function marginRequired(pos: bigint, oracle: bigint, bps: bigint): bigint {
+  const notional = (abs(pos) * oracle) / 1_000_000n;
+  return (notional * bps) / 10_000n;
+}
+
+export interface FillPreview {
+  newPosition: bigint;
+  newEntryPrice: bigint;
+  newPnl: bigint;
+  fee: bigint;
+  equity: bigint;
+  maintenanceRequired: bigint;
+  initialRequired: bigint;
+  /** Equity above initial margin (negative = shortfall) */
+  freeCollateral: bigint;
+  /** Program would reject: equity <= maintenance margin */
+  belowMaintenance: boolean;
+  /** Accepted by the program, but already under initial margin */
+  belowInitial: boolean;
+}
+
+/**
+ * Preview the taker (user) side of execute_trade: realize PnL on the closed
+ * part, charge the trading fee, average / reset entry, then check margin at
+ * the oracle price.
+ */
+export function previewFill(
+  a: MarginAccount,
+  params: MarginParams,
+  size: bigint,
+  execPrice: bigint,
+  oracle: bigint,
+  chargeFee = true
+): FillPreview {
+  const notional = (abs(size) * execPrice) / 1_000_000n;
+  const fee = chargeFee ? (notional * params.tradingFeeBps) / 10_000n : 0n;
+
+  // Realized PnL from the closing part of the fill
+  let realized = 0n;
+  const pos = a.positionSize;
+  if ((pos > 0n && size < 0n) || (pos < 0n && size > 0n)) {
+    const closeSize = abs(pos) < abs(size) ? abs(pos) : abs(size);
+    const diff = pos > 0n ? execPrice - a.entryPrice : a.entryPrice - execPrice;
+    realized = (diff * closeSize) / 1_000_000n;
+  }
+
+  const newPosition = pos + size;
+
+  // Entry price: weighted average when extending, exec price when flipping
+  let newEntryPrice = a.entryPrice;
+  if ((pos > 0n && size > 0n) || (pos < 0n && size < 0n)) {
+    const totalNotional = abs(pos) * a.entryPrice + abs(size) * execPrice;
+    const totalSize = abs(pos) + abs(size);
+    newEntryPrice = totalNotional / totalSize;
+  } else if (abs(pos) < abs(size)) {
+    newEntryPrice = execPrice;
+  }
+
+  const newPnl = a.pnl + realized - fee;
+  const after: MarginAccount = {
+    capital: a.capital,
+    pnl: newPnl,
+    positionSize: newPosition,
+    entryPrice: newEntryPrice,
+  };
+  const equity = equityMtm(after, oracle);
+  const maintenanceRequired = marginRequired(newPosition, oracle, params.maintenanceMarginBps);
+  const initialRequired = marginRequired(newPosition, oracle, params.initialMarginBps);
+
+  return {
+    newPosition,
+    newEntryPrice,
+    newPnl,
+    fee,
+    equity,
+    maintenanceRequired,
+    initialRequired,
+    freeCollateral: equity - initialRequired,
+    belowMaintenance: newPosition !== 0n && equity <= maintenanceRequired,
+    belowInitial: newPosition !== 0n && equity < initialRequired,
+  };
+}
@@ -0,0 +1,75 @@
+import { Connection, PublicKey } from "@solana/web3.js";
+import { parseAccount, parseConfig, parseParams } from "../solana/slab.js";
+import { fetchEnginePriceE6 } from "../solana/oracle.js";
+import { previewFill, FillPreview } from "./margin.js";
+
+export interface TradePreflight {
+  oraclePriceE6: bigint;
+  user: FillPreview;
+  lp: FillPreview;
+}
+
+/**
+ * Estimate post-trade margin for both sides of a trade, assuming execution
+ * at the oracle price (exact for TradeNoCpi; an estimate for matcher fills).
+ */
+export async function tradePreflight(
+  connection: Connection,
+  slabData: Buffer,
+  oracle: PublicKey,
+  lpIdx: number,
+  userIdx: number,
+  size: bigint
+): Promise<TradePreflight> {
+  const mktConfig = parseConfig(slabData);
+  const params = parseParams(slabData);
+  const oraclePriceE6 = await fetchEnginePriceE6(
+    connection,
+    oracle,
+    mktConfig.invert,
+    mktConfig.unitScale
+  );
+
+  const user = parseAccount(slabData, userIdx);
+  const lp = parseAccount(slabData, lpIdx);
+
+  return {
+    oraclePriceE6,
+    user: previewFill(user, params, size, oraclePriceE6, oraclePriceE6),
+    // LP takes the opposite side and is not charged the trading fee
+    lp: previewFill(lp, params, -size, oraclePriceE6, oraclePriceE6, false),
+  };
+}
+
+function describeSide(label: string, p: FillPreview): string {
+  return (
+    `${label}: position ${p.newPosition}, equity ${p.equity}, ` +
+    `maintenance ${p.maintenanceRequired}, initial ${p.initialRequired}, ` +
+    `free collateral ${p.freeCollateral}`
+  );
+}
+
+/**
+ * Print preflight findings to stderr. Returns false if the program would
+ * reject the trade (either side at or below maintenance margin).
+ */
+export function reportPreflight(pf: TradePreflight): boolean {
+  let ok = true;
+  for (const [label, side] of [
+    ["user", pf.user],
+    ["lp", pf.lp],
+  ] as const) {
+    if (side.belowMaintenance) {
+      console.error(`Preflight: trade would leave ${label} below maintenance margin (program will reject)`);
+      console.error(`  ${describeSide(label, side)}`);
+      ok = false;
+    } else if (side.belowInitial) {
+      console.error(`Preflight warning: ${label} would be below initial margin after this trade`);
+      console.error(`  ${describeSide(label, side)}`);
+    }
+  }
+  if (!ok) {
+    console.error("Hint: deposit more collateral or reduce --size. Use --no-preflight to send anyway.");
+  }
+  return ok;
+}
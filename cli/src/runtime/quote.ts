@@ -0,0 +1,63 @@
+/**
+ * Off-chain quote math for LP fills. The program has no order book: a
+ * TradeCpi fill is priced entirely by the LP's matcher, so quotes here
+ * assume the reference passive matcher (fixed edge around the oracle).
+ */
+
+export const PASSIVE_MATCHER_EDGE_BPS = 50n;
+const BPS_DENOM = 10_000n;
+
+const abs = (x: bigint) => (x < 0n ? -x : x);
+
+/**
+ * Passive matcher bid/ask: bid rounds down, ask rounds up.
+ */
+export function computePassiveQuote(oraclePrice: bigint, edgeBps: bigint): { bid: bigint; ask: bigint } {
+  const bid = (oraclePrice * (BPS_DENOM - edgeBps)) / BPS_DENOM;
+  const askNumer = oraclePrice * (BPS_DENOM + edgeBps);
+  const ask = (askNumer + BPS_DENOM - 1n) / BPS_DENOM;
+  return { bid, ask };
+}
+
+export interface FillQuote {
+  side: "buy" | "sell";
+  size: bigint;
+  execPrice: bigint;
+  notional: bigint;
+  /** Trading fee charged to the user (engine: notional * fee_bps / 10_000) */
+  fee: bigint;
+  /** Execution price vs oracle mid, in bps (positive = worse for the user) */
+  slippageBps: number;
+  /** Fee-inclusive effective price per unit */
+  effectivePrice: bigint;
+}
+
+/**
+ * Quote a signed size (positive = buy/long) against a passive LP.
+ */
+export function quoteFill(
+  oraclePrice: bigint,
+  size: bigint,
+  edgeBps: bigint,
+  tradingFeeBps: bigint
+): FillQuote {
+  if (size === 0n) throw new Error("size must be non-zero");
+  if (oraclePrice <= 0n) throw new Error("oracle price must be positive");
+
+  const side = size > 0n ? "buy" : "sell";
+  const { bid, ask } = computePassiveQuote(oraclePrice, edgeBps);
+  const execPrice = side === "buy" ? ask : bid;
+  const qty = abs(size);
+
+  const notional = (qty * execPrice) / 1_000_000n;
+  const fee = (notional * tradingFeeBps) / BPS_DENOM;
+
+  const diff = side === "buy" ? execPrice - oraclePrice : oraclePrice - execPrice;
+  const slippageBps = Number((diff * 1_000_000n) / oraclePrice) / 100;
+
+  // Spread the fee over the fill: buys pay more, sells receive less
+  const feePerUnit = (fee * 1_000_000n) / qty;
+  const effectivePrice = side === "buy" ? execPrice + feePerUnit : execPrice - feePerUnit;
+
+  return { side, size, execPrice, notional, fee, slippageBps, effectivePrice };
+}
@@ -0,0 +1,89 @@
+import { Connection, PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+
+/** percolator-prog SLAB_LEN */
+export const SLAB_SIZE = 1111456;
+/** percolator-prog MATCHER_CONTEXT_LEN */
+export const MATCHER_CTX_SIZE = 320;
+
+/** Headroom for signature and compute-budget fees on top of rent */
+export const FEE_BUFFER_LAMPORTS = 10_000;
+
+export interface RentItem {
+  label: string;
+  space: number;
+  lamports: number;
+}
+
+export interface RentCheck {
+  items: RentItem[];
+  required: number;
+  balance: number;
+  /** Lamports the payer is missing (0 if covered) */
+  shortfall: number;
+}
+
+/**
+ * Pure rent arithmetic, split out so it can be tested without an RPC.
+ */
+export function summarizeRent(items: RentItem[], balance: number, feeBuffer = FEE_BUFFER_LAMPORTS): RentCheck {
+  const required = items.reduce((sum, i) => sum + i.lamports, 0) + feeBuffer;
+  return {
+    items,
+    required,
+    balance,
+    shortfall: Math.max(0, required - balance),
+  };
+}
+
+/**
+ * Compute rent for the accounts about to be created and compare against
+ * the payer's balance.
+ */
+export async function checkRent(
+  connection: Connection,
+  payer: PublicKey,
+  accounts: { label: string; space: number }[]
+): Promise<RentCheck> {
+  const [balance, ...rents] = await Promise.all([
+    connection.getBalance(payer),
+    ...accounts.map((a) => connection.getMinimumBalanceForRentExemption(a.space)),
+  ]);
+  const items = accounts.map((a, i) => ({ ...a, lamports: rents[i] }));
+  return summarizeRent(items, balance);
+}
+
+const sol = (lamports: number) => (lamports / LAMPORTS_PER_SOL).toFixed(4);
+
+/**
+ * Human-readable rent report, including a top-up hint on shortfall.
+ */
+export function formatRentCheck(check: RentCheck, payer: PublicKey): string {
+  const lines = check.items.map(
+    (i) => `  ${i.label}: ${i.space} bytes -> ${sol(i.lamports)} SOL`
+  );
+  lines.push(`  Required (incl. fees): ${sol(check.required)} SOL`);
+  lines.push(`  Payer balance:         ${sol(check.balance)} SOL`);
+  if (check.shortfall > 0) {
+    lines.push(`  Shortfall:             ${sol(check.shortfall)} SOL`);
+    lines.push(
+      `Top up with: solana transfer ${payer.toBase58()} ${sol(check.shortfall)}` +
+        ` (devnet: solana airdrop ${Math.ceil(check.shortfall / LAMPORTS_PER_SOL)} ${payer.toBase58()})`
+    );
+  }
+  return lines.join("\n");
+}
+
+/**
+ * Throw with a rent-specific message if the payer cannot cover the accounts.
+ */
+export async function ensureRent(
+  connection: Connection,
+  payer: PublicKey,
+  accounts: { label: string; space: number }[]
+): Promise<RentCheck> {
+  const check = await checkRent(connection, payer, accounts);
+  if (check.shortfall > 0) {
+    throw new Error(`Insufficient SOL for rent:\n${formatRentCheck(check, payer)}`);
+  }
+  return check;
+}
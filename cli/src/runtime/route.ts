@@ -0,0 +1,105 @@
+/**
+ * Off-chain route planning across several slabs for the same instrument.
+ * Each slab is a candidate venue with one quoted price and a size capacity
+ * (how much its LP can absorb before execute_trade's maintenance check
+ * rejects it). The plan
+ * fills greedily from the best price, like a smart-order router would.
+ */
+
+import { MarginAccount, equityMtm } from "./margin.js";
+
+const abs = (x: bigint) => (x < 0n ? -x : x);
+
+/** Engine MAX_POSITION_ABS */
+const MAX_POSITION_ABS = 100_000_000_000_000_000_000n;
+
+export interface RouteCandidate {
+  slab: string;
+  lpIdx: number;
+  /** Price the taker would pay (buy) or receive (sell), e6 */
+  execPrice: bigint;
+  /** Max |size| this venue can fill */
+  capacity: bigint;
+}
+
+export interface RouteLeg {
+  slab: string;
+  lpIdx: number;
+  size: bigint;
+  execPrice: bigint;
+}
+
+export interface RoutePlan {
+  legs: RouteLeg[];
+  filled: bigint;
+  unfilled: bigint;
+  /** Volume-weighted average exec price over filled size (0 if nothing fills) */
+  vwap: bigint;
+}
+
+/**
+ * Max |size| an LP can take on the opposite side of a taker fill of the
+ * given sign while execute_trade's LP check still passes: equity strictly
+ * above maintenance margin on the new position at `oracle`. An LP already at
+ * or below maintenance can only be closed out exactly, so it gets no
+ * capacity.
+ */
+export function lpCapacity(
+  lp: MarginAccount,
+  maintenanceMarginBps: bigint,
+  oracle: bigint,
+  takerSide: 1n | -1n
+): bigint {
+  if (oracle === 0n) return 0n;
+  const equity = equityMtm(lp, oracle);
+  // Largest |pos| with marginRequired(pos) < equity, undoing both floor divisions
+  let maxPos: bigint;
+  if (maintenanceMarginBps === 0n) {
+    maxPos = equity > 0n ? MAX_POSITION_ABS : 0n;
+  } else {
+    const maxValue = (equity * 10_000n + maintenanceMarginBps - 1n) / maintenanceMarginBps - 1n;
+    maxPos = ((maxValue + 1n) * 1_000_000n + oracle - 1n) / oracle - 1n;
+    if (maxPos < 0n) maxPos = 0n;
+  }
+  const pos = abs(lp.positionSize);
+  if (pos > maxPos) return 0n;
+  // LP moves by -takerSide: a long LP reduces when the taker buys
+  const reducing = lp.positionSize !== 0n && (lp.positionSize > 0n) === (takerSide > 0n);
+  if (reducing) return pos + maxPos;
+  return maxPos - pos;
+}
+
+/**
+ * Split a signed size (positive = buy) across candidates, best price first.
+ * Ties go to the venue with more capacity.
+ */
+export function planRoute(candidates: RouteCandidate[], size: bigint): RoutePlan {
+  const buy = size > 0n;
+  const sorted = [...candidates].sort((a, b) => {
+    if (a.execPrice !== b.execPrice) {
+      const better = buy ? a.execPrice < b.execPrice : a.execPrice > b.execPrice;
+      return better ? -1 : 1;
+    }
+    return a.capacity === b.capacity ? 0 : a.capacity > b.capacity ? -1 : 1;
+  });
+
+  const legs: RouteLeg[] = [];
+  let remaining = abs(size);
+  let notional = 0n;
+  for (const c of sorted) {
+    if (remaining === 0n) break;
+    const take = c.capacity < remaining ? c.capacity : remaining;
+    if (take === 0n) continue;
+    legs.push({ slab: c.slab, lpIdx: c.lpIdx, size: buy ? take : -take, execPrice: c.execPrice });
+    notional += take * c.execPrice;
+    remaining -= take;
+  }
+
+  const filled = abs(size) - remaining;
+  return {
+    legs,
+    filled,
+    unfilled: remaining,
+    vwap: filled === 0n ? 0n : notional / filled,
+  };
+}
@@ -0,0 +1,122 @@
+/**
+ * Off-chain price-shock stress test over slab accounts. Each account is
+ * re-marked at a shocked oracle price using the margin mirror in
+ * margin.ts, and accounts that would fall to or below maintenance are
+ * listed in the order the crank reaches them: it sweeps each slab in index
+ * order, one window per step, starting at the current step's window.
+ */
+
+import { MarginAccount, MarginParams, equityMtm, marginRequired } from "./margin.js";
+
+/** Accounts per crank step and slab capacity, as in the engine (WINDOW, MAX_ACCOUNTS) */
+export const CRANK_WINDOW = 256;
+export const MAX_ACCOUNTS = 4096;
+
+export interface StressAccount extends MarginAccount {
+  slab: string;
+  idx: number;
+}
+
+export interface StressResult {
+  slab: string;
+  idx: number;
+  positionSize: bigint;
+  shockedPrice: bigint;
+  equityBefore: bigint;
+  equity: bigint;
+  maintenanceRequired: bigint;
+  /** Maintenance minus equity, 0 if covered */
+  shortfall: bigint;
+  /** Equity <= maintenance with an open position, same as the crank's check */
+  liquidatable: boolean;
+}
+
+export interface StressReport {
+  results: StressResult[];
+  /** Liquidatable accounts in crank sweep order */
+  liquidations: StressResult[];
+  equityBefore: bigint;
+  equityAfter: bigint;
+  totalShortfall: bigint;
+}
+
+/**
+ * Parse a shock like "-20%", "+5%" or "-12.5" (percent) into bps. Shocks at or
+ * below -100% would take the price to zero, which the oracle never reports.
+ */
+export function parseShockBps(value: string): bigint {
+  const m = /^([+-]?)(\d+)(?:\.(\d{1,2}))?%?$/.exec(value.trim());
+  if (!m) throw new Error(`invalid shock "${value}" (expected e.g. -20%)`);
+  const frac = (m[3] ?? "").padEnd(2, "0");
+  const bps = BigInt(m[2]) * 100n + BigInt(frac);
+  const signed = m[1] === "-" ? -bps : bps;
+  if (signed <= -10_000n) throw new Error(`shock "${value}" must be above -100%`);
+  return signed;
+}
+
+/**
+ * Oracle price after a shock, rounded toward zero and floored at 1.
+ */
+export function shockPrice(price: bigint, shockBps: bigint): bigint {
+  const p = (price * (10_000n + shockBps)) / 10_000n;
+  return p > 0n ? p : 1n;
+}
+
+/**
+ * Re-mark one account at `oracle` (current) and `shocked`.
+ */
+export function stressAccount(
+  a: StressAccount,
+  params: MarginParams,
+  oracle: bigint,
+  shocked: bigint
+): StressResult {
+  const equity = equityMtm(a, shocked);
+  const maintenanceRequired = marginRequired(a.positionSize, shocked, params.maintenanceMarginBps);
+  const open = a.positionSize !== 0n;
+  return {
+    slab: a.slab,
+    idx: a.idx,
+    positionSize: a.positionSize,
+    shockedPrice: shocked,
+    equityBefore: equityMtm(a, oracle),
+    equity,
+    maintenanceRequired,
+    shortfall: open && maintenanceRequired > equity ? maintenanceRequired - equity : 0n,
+    liquidatable: open && equity <= maintenanceRequired,
+  };
+}
+
+/**
+ * First account index the next crank visits, given the engine's crank_step.
+ */
+export function sweepStart(crankStep: number): number {
+  return (crankStep * CRANK_WINDOW) % MAX_ACCOUNTS;
+}
+
+/**
+ * Aggregate per-account results and order liquidations the way the crank
+ * reaches them: slabs in input order, then index order from each slab's
+ * sweep start (`sweepStarts`, default 0), wrapping at MAX_ACCOUNTS.
+ */
+export function summarizeStress(
+  results: StressResult[],
+  sweepStarts: Map<string, number> = new Map()
+): StressReport {
+  const slabOrder = new Map<string, number>();
+  for (const r of results) {
+    if (!slabOrder.has(r.slab)) slabOrder.set(r.slab, slabOrder.size);
+  }
+  const position = (r: StressResult) =>
+    (r.idx - (sweepStarts.get(r.slab) ?? 0) + MAX_ACCOUNTS) % MAX_ACCOUNTS;
+  const liquidations = results
+    .filter((r) => r.liquidatable)
+    .sort((a, b) => slabOrder.get(a.slab)! - slabOrder.get(b.slab)! || position(a) - position(b));
+  return {
+    results,
+    liquidations,
+    equityBefore: results.reduce((s, r) => s + r.equityBefore, 0n),
+    equityAfter: results.reduce((s, r) => s + r.equity, 0n),
+    totalShortfall: results.reduce((s, r) => s + r.shortfall, 0n),
+  };
+}
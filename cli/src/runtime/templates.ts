@@ -0,0 +1,102 @@
+/**
+ * Named parameter sets for init-market, so new markets don't need every
+ * risk and oracle knob spelled out on the command line. Explicit flags
+ * always override the template. Oracle feed, mint, invert and unit scale
+ * are market-specific and never come from a template.
+ */
+
+export interface MarketTemplate {
+  maxStalenessSecs: string;
+  confFilterBps: string;
+  warmupPeriodSlots: string;
+  maintenanceMarginBps: string;
+  initialMarginBps: string;
+  tradingFeeBps: string;
+  maxAccounts: string;
+  newAccountFee: string;
+  riskReductionThreshold: string;
+  maintenanceFeePerSlot: string;
+  maxCrankStalenessSlots: string;
+  liquidationFeeBps: string;
+  liquidationFeeCap: string;
+  liquidationBufferBps: string;
+  minLiquidationAbs: string;
+}
+
+/** Parameters used by scripts/setup-devnet-market.ts */
+const DEVNET: MarketTemplate = {
+  maxStalenessSecs: "3600",
+  confFilterBps: "500",
+  warmupPeriodSlots: "10",
+  maintenanceMarginBps: "500",
+  initialMarginBps: "1000",
+  tradingFeeBps: "10",
+  maxAccounts: "1024",
+  newAccountFee: "1000000",
+  riskReductionThreshold: "0",
+  maintenanceFeePerSlot: "0",
+  maxCrankStalenessSlots: "200",
+  liquidationFeeBps: "100",
+  liquidationFeeCap: "1000000000",
+  liquidationBufferBps: "50",
+  minLiquidationAbs: "100000",
+};
+
+export const MARKET_TEMPLATES: Record<string, MarketTemplate> = {
+  devnet: DEVNET,
+  // Liquid majors: tight oracle, 20x initial leverage
+  major: {
+    ...DEVNET,
+    maxStalenessSecs: "60",
+    confFilterBps: "100",
+    maintenanceMarginBps: "250",
+    initialMarginBps: "500",
+    tradingFeeBps: "5",
+    liquidationBufferBps: "25",
+  },
+  // Thin or volatile underlyings: 5x initial leverage, longer warmup
+  conservative: {
+    ...DEVNET,
+    maxStalenessSecs: "120",
+    confFilterBps: "200",
+    warmupPeriodSlots: "100",
+    maintenanceMarginBps: "1000",
+    initialMarginBps: "2000",
+    tradingFeeBps: "20",
+    liquidationFeeBps: "150",
+    liquidationBufferBps: "100",
+  },
+};
+
+/**
+ * Merge a named template with explicit overrides. Without a template every
+ * field must be given explicitly; missing ones are reported together.
+ */
+export function resolveMarketParams(
+  template: string | undefined,
+  overrides: Partial<MarketTemplate>
+): MarketTemplate {
+  let base: Partial<MarketTemplate> = {};
+  if (template !== undefined) {
+    const t = MARKET_TEMPLATES[template];
+    if (!t) {
+      throw new Error(
+        `Unknown market template "${template}" (available: ${Object.keys(MARKET_TEMPLATES).join(", ")})`
+      );
+    }
+    base = t;
+  }
+
+  const merged: Partial<MarketTemplate> = { ...base };
+  for (const [k, v] of Object.entries(overrides)) {
+    if (v !== undefined) merged[k as keyof MarketTemplate] = v;
+  }
+
+  const missing = (Object.keys(DEVNET) as (keyof MarketTemplate)[]).filter(
+    (k) => merged[k] === undefined
+  );
+  if (missing.length > 0) {
+    throw new Error(`Missing market parameters (pass them or use --template): ${missing.join(", ")}`);
+  }
+  return merged as MarketTemplate;
+}
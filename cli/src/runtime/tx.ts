@@ -9,7 +9,9 @@ import {
   AccountMeta,
   ComputeBudgetProgram,
 } from "@solana/web3.js";
-import { parseErrorFromLogs } from "../abi/errors.js";
+import { describeFailure, decodeCustomError } from "../abi/errors.js";
+import { decodeTradeResult } from "../abi/decode.js";
+import { parseLogTree, formatLogTree, summarizeCu } from "./logs.js";
 
 export interface BuildIxParams {
   programId: PublicKey;
@@ -34,12 +36,17 @@ export interface TxResult {
   err: string | null;
   hint?: string;
   logs: string[];
+  /** Percolator program the transaction targeted; scopes error decoding */
+  programId?: string;
   unitsConsumed?: number;
+  /** Base64 return data set by the percolator program (trade fill result), if any */
+  returnData?: string;
 }
 
 export interface SimulateOrSendParams {
   connection: Connection;
-  ix: TransactionInstruction;
+  /** One instruction, or several that must land atomically in one transaction */
+  ix: TransactionInstruction | TransactionInstruction[];
   signers: Keypair[];
   simulate: boolean;
   commitment?: Commitment;
@@ -66,7 +73,11 @@ export async function simulateOrSend(
     );
   }
 
-  tx.add(ix);
+  const ixs = Array.isArray(ix) ? ix : [ix];
+  tx.add(...ixs);
+  // Callers pass percolator instructions; the compute budget ix is ours
+  const programId = ixs[0].programId.toBase58();
+  const ixProgramIds = tx.instructions.map((i) => i.programId.toBase58());
   const latestBlockhash = await connection.getLatestBlockhash(commitment);
   tx.recentBlockhash = latestBlockhash.blockhash;
   tx.feePayer = signers[0].publicKey;
@@ -79,13 +90,7 @@ export async function simulateOrSend(
     let hint: string | undefined;
 
     if (result.value.err) {
-      const parsed = parseErrorFromLogs(logs);
-      if (parsed) {
-        err = `${parsed.name} (0x${parsed.code.toString(16)})`;
-        hint = parsed.hint;
-      } else {
-        err = JSON.stringify(result.value.err);
-      }
+      ({ err, hint } = describeFailure(logs, result.value.err, programId, ixProgramIds));
     }
 
     return {
@@ -94,7 +99,9 @@ export async function simulateOrSend(
       err,
       hint,
       logs,
+      programId,
       unitsConsumed: result.value.unitsConsumed ?? undefined,
+      returnData: ownReturnData(result.value.returnData, programId),
     };
   }
 
@@ -127,13 +134,7 @@ export async function simulateOrSend(
     let hint: string | undefined;
 
     if (confirmation.value.err) {
-      const parsed = parseErrorFromLogs(logs);
-      if (parsed) {
-        err = `${parsed.name} (0x${parsed.code.toString(16)})`;
-        hint = parsed.hint;
-      } else {
-        err = JSON.stringify(confirmation.value.err);
-      }
+      ({ err, hint } = describeFailure(logs, confirmation.value.err, programId, ixProgramIds));
     }
 
     return {
@@ -142,23 +143,92 @@ export async function simulateOrSend(
       err,
       hint,
       logs,
+      programId,
+      unitsConsumed: txInfo?.meta?.computeUnitsConsumed ?? undefined,
+      returnData: ownReturnData(metaReturnData(txInfo?.meta), programId),
     };
   } catch (e: unknown) {
+    // Preflight failures surface as SendTransactionError with logs attached
     const message = e instanceof Error ? e.message : String(e);
+    const logs: string[] = (e as { logs?: string[] })?.logs ?? [];
+    const { err, hint } = describeFailure(logs, message, programId, ixProgramIds);
     return {
       signature: "",
       slot: 0,
-      err: message,
-      logs: [],
+      err,
+      hint,
+      logs,
+      programId,
     };
   }
 }
 
+interface ReturnData {
+  programId: string;
+  data: [string, string];
+}
+
+/**
+ * getTransaction returns meta.returnData, but not every web3.js version types it.
+ */
+function metaReturnData(meta: unknown): ReturnData | null | undefined {
+  return (meta as { returnData?: ReturnData | null } | null | undefined)?.returnData;
+}
+
+/**
+ * Return data only counts as a fill when percolator set it; a matcher or
+ * other program's data must not be decoded as a trade result.
+ */
+function ownReturnData(rd: ReturnData | null | undefined, programId: string): string | undefined {
+  return rd?.programId === programId ? rd.data[0] || undefined : undefined;
+}
+
+/**
+ * Fill summary lines from trade return data (empty if there is none).
+ */
+export function formatFill(returnData: string | undefined): string[] {
+  const fill = returnData ? decodeTradeResult(Buffer.from(returnData, "base64")) : null;
+  if (!fill) return [];
+  return [
+    `Fill: ${fill.execSize} @ ${fill.execPriceE6} (oracle ${fill.oraclePriceE6}), fee ${fill.fee}`,
+    `Position: ${fill.userPosition} @ ${fill.userEntryPriceE6}, capital ${fill.userCapital}`,
+  ];
+}
+
+/**
+ * Render logs as a CPI tree plus per-program CU breakdown, with custom
+ * program errors decoded at failing percolator invocations. Other programs
+ * share the custom-error space, so their failures stay raw.
+ */
+export function formatTrace(logs: string[], programId?: string): string[] {
+  const tree = parseLogTree(logs);
+  if (tree.length === 0) {
+    return logs.length > 0 ? ["Logs:", ...logs.map((l) => `  ${l}`)] : [];
+  }
+  const decode = (error: string, failedId: string) => {
+    const parsed = failedId === programId ? decodeCustomError(error) : null;
+    return parsed ? `${error} (${parsed.name})` : error;
+  };
+  const lines = ["Trace:"];
+  formatLogTree(tree, undefined, decode).forEach((l) => lines.push(`  ${l}`));
+  lines.push("CU by program:");
+  for (const p of summarizeCu(tree)) {
+    const calls = p.invocations > 1 ? ` (${p.invocations} calls)` : "";
+    lines.push(`  ${p.programId}: ${p.selfUnits.toLocaleString()}${calls}`);
+  }
+  return lines;
+}
+
 /**
  * Format transaction result for output.
+ * With `verbose`, the CPI trace and CU breakdown follow the summary.
  */
-export function formatResult(result: TxResult, jsonMode: boolean): string {
+export function formatResult(result: TxResult, jsonMode: boolean, verbose = false): string {
   if (jsonMode) {
+    if (verbose) {
+      const tree = parseLogTree(result.logs);
+      return JSON.stringify({ ...result, invocations: tree, cuByProgram: summarizeCu(tree) }, null, 2);
+    }
     return JSON.stringify(result, null, 2);
   }
 
@@ -172,7 +242,9 @@ export function formatResult(result: TxResult, jsonMode: boolean): string {
     if (result.unitsConsumed !== undefined) {
       lines.push(`Compute Units: ${result.unitsConsumed.toLocaleString()}`);
     }
-    if (result.logs.length > 0) {
+    if (verbose) {
+      lines.push(...formatTrace(result.logs, result.programId));
+    } else if (result.logs.length > 0) {
       lines.push("Logs:");
       result.logs.forEach((log) => lines.push(`  ${log}`));
     }
@@ -182,9 +254,13 @@ export function formatResult(result: TxResult, jsonMode: boolean): string {
     if (result.unitsConsumed !== undefined) {
       lines.push(`Compute Units: ${result.unitsConsumed.toLocaleString()}`);
     }
+    lines.push(...formatFill(result.returnData));
     if (result.signature !== "(simulated)") {
       lines.push(`Explorer: https://explorer.solana.com/tx/${result.signature}`);
     }
+    if (verbose) {
+      lines.push(...formatTrace(result.logs, result.programId));
+    }
   }
 
   return lines.join("\n");
@@ -0,0 +1,87 @@
+import { Connection, PublicKey } from "@solana/web3.js";
+
+/**
+ * Oracle readers - mirror prog/src oracle::read_engine_price_e6.
+ * Staleness / confidence checks are NOT applied: these are for off-chain
+ * estimates only, the program remains the authority.
+ */
+
+/** Pyth Solana Receiver program (PriceUpdateV2 owner) */
+export const PYTH_RECEIVER_PROGRAM_ID = new PublicKey("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/** Chainlink OCR2 store program */
+export const CHAINLINK_OCR2_PROGRAM_ID = new PublicKey("HEvSKofvBgfaexv23kMabbYqxasxU3mQ4ibBMEmJWHny");
+
+// PriceUpdateV2 offsets
+const PYTH_MIN_LEN = 134;
+const PYTH_OFF_PRICE = 74;
+const PYTH_OFF_EXPO = 90;
+
+// Chainlink OCR2 offsets
+const CL_MIN_LEN = 224;
+const CL_OFF_DECIMALS = 138;
+const CL_OFF_ANSWER = 216;
+
+const INVERSION_CONSTANT = 1_000_000_000_000n; // 1e12
+
+function toE6(raw: bigint, expo: number): bigint {
+  const scale = expo + 6;
+  return scale >= 0 ? raw * 10n ** BigInt(scale) : raw / 10n ** BigInt(-scale);
+}
+
+/**
+ * Parse a raw oracle account into a price in e6, dispatching on owner
+ * exactly like the program does.
+ */
+export function parseOraclePriceE6(owner: PublicKey, data: Buffer): bigint {
+  if (owner.equals(PYTH_RECEIVER_PROGRAM_ID)) {
+    if (data.length < PYTH_MIN_LEN) throw new Error("Pyth PriceUpdateV2 account too short");
+    const price = data.readBigInt64LE(PYTH_OFF_PRICE);
+    const expo = data.readInt32LE(PYTH_OFF_EXPO);
+    if (price <= 0n) throw new Error("Pyth price is not positive");
+    return toE6(price, expo);
+  }
+  if (owner.equals(CHAINLINK_OCR2_PROGRAM_ID)) {
+    if (data.length < CL_MIN_LEN) throw new Error("Chainlink account too short");
+    const decimals = data.readUInt8(CL_OFF_DECIMALS);
+    // i128 answer
+    const answer =
+      (data.readBigInt64LE(CL_OFF_ANSWER + 8) << 64n) | data.readBigUInt64LE(CL_OFF_ANSWER);
+    if (answer <= 0n) throw new Error("Chainlink answer is not positive");
+    return toE6(answer, -decimals);
+  }
+  throw new Error(`Unsupported oracle owner: ${owner.toBase58()}`);
+}
+
+/**
+ * Apply the market's inversion and unit scaling to a raw e6 price,
+ * giving the price the engine uses for margin math.
+ */
+export function toEnginePriceE6(rawE6: bigint, invert: number, unitScale: number): bigint {
+  let price = rawE6;
+  if (invert !== 0) {
+    if (price === 0n) throw new Error("Cannot invert zero price");
+    price = INVERSION_CONSTANT / price;
+  }
+  if (unitScale > 1) {
+    price = price / BigInt(unitScale);
+  }
+  if (price === 0n) throw new Error("Engine price rounds to zero");
+  return price;
+}
+
+/**
+ * Fetch an oracle account and return the engine-space price in e6.
+ */
+export async function fetchEnginePriceE6(
+  connection: Connection,
+  oracle: PublicKey,
+  invert: number,
+  unitScale: number
+): Promise<bigint> {
+  const info = await connection.getAccountInfo(oracle);
+  if (!info) {
+    throw new Error(`Oracle account not found: ${oracle.toBase58()}`);
+  }
+  const raw = parseOraclePriceE6(info.owner, Buffer.from(info.data));
+  return toEnginePriceE6(raw, invert, unitScale);
+}
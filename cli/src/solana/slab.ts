@@ -2,7 +2,7 @@ import { Connection, PublicKey } from "@solana/web3.js";
 
 // Constants from Rust (updated for funding/threshold params 2026-01)
 const MAGIC: bigint = 0x504552434f4c4154n; // "PERCOLAT"
-const HEADER_LEN = 72;    // SlabHeader: magic(8) + version(4) + bump(1) + _padding(3) + admin(32) + _reserved(24)
+const HEADER_LEN = 72;    // SlabHeader: magic(8) + version(4) + bump(1) + flags(1) + _padding(2) + admin(32) + _reserved(24)
 const CONFIG_OFFSET = HEADER_LEN;  // MarketConfig starts right after header
 // MarketConfig: collateral_mint(32) + vault_pubkey(32) + index_feed_id(32) + max_staleness_secs(8) +
 //               conf_filter_bps(2) + bump(1) + invert(1) + unit_scale(4) +
@@ -12,6 +12,8 @@ const CONFIG_OFFSET = HEADER_LEN;  // MarketConfig starts right after header
 //               thresh_step_bps(8) + thresh_alpha_bps(8) + thresh_min(16) + thresh_max(16) + thresh_min_step(16)
 const CONFIG_LEN = 256;
 const RESERVED_OFF = 48;  // Offset of _reserved field within SlabHeader
+/** SlabHeader.flags bit: market accepts only position-reducing trades */
+export const FLAG_REDUCE_ONLY = 1;
 
 /**
  * Slab header (72 bytes)
@@ -20,9 +22,14 @@ export interface SlabHeader {
   magic: bigint;
   version: number;
   bump: number;
+  /** Market flags; bit 0 = reduce-only */
+  flags: number;
+  reduceOnly: boolean;
   admin: PublicKey;
   nonce: bigint;
   lastThrUpdateSlot: bigint;
+  /** Base tokens deposited below one unit, not yet credited to the engine */
+  dustBase: bigint;
 }
 
 /**
@@ -70,19 +77,24 @@ export function parseHeader(data: Buffer): SlabHeader {
 
   const version = data.readUInt32LE(8);
   const bump = data.readUInt8(12);
+  const flags = data.readUInt8(13);
   const admin = new PublicKey(data.subarray(16, 48));
 
-  // Reserved field: nonce at [0..8], lastThrUpdateSlot at [8..16]
+  // Reserved field: nonce at [0..8], lastThrUpdateSlot at [8..16], dustBase at [16..24]
   const nonce = data.readBigUInt64LE(RESERVED_OFF);
   const lastThrUpdateSlot = data.readBigUInt64LE(RESERVED_OFF + 8);
+  const dustBase = data.readBigUInt64LE(RESERVED_OFF + 16);
 
   return {
     magic,
     version,
     bump,
+    flags,
+    reduceOnly: (flags & FLAG_REDUCE_ONLY) !== 0,
     admin,
     nonce,
     lastThrUpdateSlot,
+    dustBase,
   };
 }
 
@@ -135,6 +147,34 @@ export function parseConfig(data: Buffer): MarketConfig {
   };
 }
 
+/**
+ * Funding parameters from MarketConfig (after unit_scale, at config offset 112).
+ */
+export interface FundingParams {
+  horizonSlots: bigint;
+  kBps: bigint;
+  invScaleNotionalE6: bigint;
+  maxPremiumBps: bigint;
+  maxBpsPerSlot: bigint;
+}
+
+const CONFIG_FUNDING_OFF = 112;
+
+export function parseFundingParams(data: Buffer): FundingParams {
+  const minLen = CONFIG_OFFSET + CONFIG_LEN;
+  if (data.length < minLen) {
+    throw new Error(`Slab data too short for config: ${data.length} < ${minLen}`);
+  }
+  const off = CONFIG_OFFSET + CONFIG_FUNDING_OFF;
+  return {
+    horizonSlots: data.readBigUInt64LE(off),
+    kBps: data.readBigUInt64LE(off + 8),
+    invScaleNotionalE6: readU128LE(data, off + 16),
+    maxPremiumBps: data.readBigInt64LE(off + 32),
+    maxBpsPerSlot: data.readBigInt64LE(off + 40),
+  };
+}
+
 /**
  * Read nonce from slab header reserved field.
  */
@@ -194,10 +234,12 @@ const ENGINE_LIFETIME_FORCE_CLOSES_OFF = 392; // lifetime_force_realize_closes:
 // - numUsed (u16=1) at slab 87360 = engine 87032
 // - nextAccountId (u64) at slab 87368 = engine 87040
 // - accounts start at slab 95584 = engine 95256 (owner pubkeys verified)
-const ENGINE_BITMAP_OFF = 86520;          // slab 86848 = 328 + 86520 (bitmap word 0)
-const ENGINE_NUM_USED_OFF = 87032;        // slab 87360 = 328 + 87032 (u16)
-const ENGINE_NEXT_ACCOUNT_ID_OFF = 87040; // slab 87368 = 328 + 87040 (u64)
-const ENGINE_ACCOUNTS_OFF = 95256;        // slab 95584 = 328 + 95256
+// Shifted +72 for lifetime_volume and the sweep summary base
+// (u128 x4 + u64, inserted after lifetime_force_realize_closes)
+const ENGINE_BITMAP_OFF = 86592;          // slab 86920 = 328 + 86592 (bitmap word 0)
+const ENGINE_NUM_USED_OFF = 87104;        // slab 87432 = 328 + 87104 (u16)
+const ENGINE_NEXT_ACCOUNT_ID_OFF = 87112; // slab 87440 = 328 + 87112 (u64)
+const ENGINE_ACCOUNTS_OFF = 95328;        // slab 95656 = 328 + 95328
 
 const BITMAP_WORDS = 64;
 const MAX_ACCOUNTS = 4096;
@@ -211,3 +211,31 @@ export function validateU16(value: string, field: string): number {
   }
   return num;
 }
+
+export interface TradeLeg {
+  slab: PublicKey;
+  lpIdx: number;
+  userIdx: number;
+  size: bigint;
+}
+
+/**
+ * Validate a trade leg of the form <slab>:<lp-idx>:<user-idx>:<size>.
+ */
+export function validateTradeLeg(value: string, field: string): TradeLeg {
+  const parts = value.split(":");
+  if (parts.length !== 4) {
+    throw new ValidationError(field, `"${value}" must be <slab>:<lp-idx>:<user-idx>:<size>`);
+  }
+  const [slab, lpIdx, userIdx, size] = parts;
+  const leg = {
+    slab: validatePublicKey(slab, field),
+    lpIdx: validateIndex(lpIdx, field),
+    userIdx: validateIndex(userIdx, field),
+    size: validateI128(size, field),
+  };
+  if (leg.size === 0n) {
+    throw new ValidationError(field, "size must be non-zero");
+  }
+  return leg;
+}
@@ -20,6 +20,8 @@ import {
   encodeCloseAccount,
   encodeTopUpInsurance,
   encodeSetRiskThreshold,
+  encodeSetReduceOnly,
+  encodeSetOracleParams,
   encodeUpdateAdmin,
   encodeInitLP,
   IX_TAG,
@@ -173,6 +175,8 @@ console.log("\nTesting instruction encoders...\n");
   assert(IX_TAG.TradeCpi === 10, "TradeCpi tag");
   assert(IX_TAG.SetRiskThreshold === 11, "SetRiskThreshold tag");
   assert(IX_TAG.UpdateAdmin === 12, "UpdateAdmin tag");
+  assert(IX_TAG.SetReduceOnly === 15, "SetReduceOnly tag");
+  assert(IX_TAG.SetOracleParams === 16, "SetOracleParams tag");
   console.log("✓ IX_TAG values");
 }
 
@@ -287,6 +291,20 @@ console.log("\nTesting instruction encoders...\n");
   console.log("✓ encodeSetRiskThreshold");
 }
 
+// Test SetReduceOnly encoding (2 bytes: tag + u8)
+{
+  assertBuf(encodeSetReduceOnly({ enabled: true }), [IX_TAG.SetReduceOnly, 1], "SetReduceOnly on");
+  assertBuf(encodeSetReduceOnly({ enabled: false }), [IX_TAG.SetReduceOnly, 0], "SetReduceOnly off");
+  console.log("✓ encodeSetReduceOnly");
+}
+
+// Test SetOracleParams encoding (11 bytes: tag + u64 + u16)
+{
+  const data = encodeSetOracleParams({ maxStalenessSecs: "60", confFilterBps: 250 });
+  assertBuf(data, [IX_TAG.SetOracleParams, 60, 0, 0, 0, 0, 0, 0, 0, 250, 0], "SetOracleParams");
+  console.log("✓ encodeSetOracleParams");
+}
+
 // Test UpdateAdmin encoding (33 bytes: tag + pubkey)
 {
   const newAdmin = new PublicKey("11111111111111111111111111111111");
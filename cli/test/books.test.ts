@@ -0,0 +1,90 @@
+/**
+ * Accounting snapshot tests
+ */
+
+import { settledPnl, computeBooks, formatBooks } from "../src/runtime/books.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing books...\n");
+
+const engine = {
+  vault: 10_000n,
+  insuranceFund: { balance: 500n, feeRevenue: 200n },
+  lossAccum: 0n,
+  fundingIndexQpbE6: 0n,
+};
+const flat = (capital: bigint, pnl: bigint) => ({ capital, pnl, positionSize: 0n, entryPrice: 0n, fundingIndex: 0n });
+
+{
+  const acct = { capital: 0n, pnl: 0n, positionSize: 3n, entryPrice: 0n, fundingIndex: 0n };
+  assert(settledPnl(acct, 1n) === -1n, "payer rounds up");
+  assert(settledPnl({ ...acct, positionSize: -3n }, 1n) === 0n, "receiver truncates");
+  assert(settledPnl(acct, 2_000_000n) === -6n, "whole payment");
+  assert(settledPnl({ ...flat(0n, 7n) }, 5n) === 7n, "flat account unaffected");
+  console.log("✓ settledPnl");
+}
+
+{
+  const b = computeBooks(engine, [flat(6_000n, 1_000n), flat(3_000n, -1_000n)], { tokenVault: 10_000n });
+  assert(b.totalCapital === 9_000n, "sums capital");
+  assert(b.netPnl === 0n, "nets pnl");
+  assert(b.liabilities === 9_500n, "capital + insurance");
+  assert(b.surplus === 500n, "surplus");
+  assert(b.tokenVaultDiff === 0n, "token vault matches");
+  assert(b.balanced, "balanced");
+  console.log("✓ computeBooks balanced");
+}
+
+{
+  // Unsettled losses net against capital
+  const b = computeBooks(engine, [flat(9_000n, -2_000n)]);
+  assert(b.liabilities === 7_500n, "negative pnl reduces liabilities");
+  assert(b.tokenVault === null && b.tokenVaultDiff === null, "no token vault");
+  assert(b.unrealizedPnl === null, "no oracle, no unrealized pnl");
+  assert(b.balanced, "balanced without token vault");
+  console.log("✓ computeBooks net loss");
+}
+
+{
+  const short = computeBooks(engine, [flat(9_000n, 1_000n)], { tokenVault: 10_000n });
+  assert(short.surplus === -500n, "engine shortfall");
+  assert(!short.balanced, "flags engine shortfall");
+  assert(formatBooks(short).includes("IMBALANCE"), "highlights imbalance");
+
+  const covered = computeBooks({ ...engine, lossAccum: 500n }, [flat(9_000n, 1_000n)], { tokenVault: 10_000n });
+  assert(covered.balanced, "loss accumulator counts as assets");
+
+  const drained = computeBooks(engine, [flat(9_000n, 0n)], { tokenVault: 9_999n });
+  assert(drained.tokenVaultDiff === -1n, "token vault below counter");
+  assert(!drained.balanced, "flags token vault shortfall");
+  console.log("✓ computeBooks imbalance");
+}
+
+{
+  // Engine vault is in units; the token vault holds units * scale + dust
+  const scaled = computeBooks(engine, [flat(9_000n, 0n)], { tokenVault: 10_000_250n, unitScale: 1000, dustBase: 250n });
+  assert(scaled.vaultBase === 10_000_250n, "vault converted to base");
+  assert(scaled.tokenVaultDiff === 0n, "dust accounted for");
+  assert(scaled.balanced, "scaled market balances");
+  assert(formatBooks(scaled).includes("dust 250"), "shows dust");
+
+  const missingDust = computeBooks(engine, [flat(9_000n, 0n)], { tokenVault: 10_000_000n, unitScale: 1000, dustBase: 250n });
+  assert(missingDust.tokenVaultDiff === -250n, "missing dust");
+  assert(!missingDust.balanced, "flags missing dust");
+  console.log("✓ computeBooks unit scale");
+}
+
+{
+  const long = { capital: 5_000n, pnl: 0n, positionSize: 2_000_000n, entryPrice: 1_000_000n, fundingIndex: 0n };
+  const short = { ...long, positionSize: -1_000_000n };
+  const b = computeBooks(engine, [long, short], { oraclePriceE6: 1_100_000n });
+  assert(b.unrealizedPnl === 100_000n, "long gains, short loses at oracle");
+  assert(b.liabilities === 10_500n, "unrealized pnl stays out of liabilities");
+  assert(formatBooks(b).includes("Unrealized PnL (mark): 100000"), "memo line");
+  console.log("✓ computeBooks unrealized pnl");
+}
+
+console.log("\n✅ All books tests passed!");
@@ -0,0 +1,183 @@
+import {
+  encodeDepositCollateral,
+  encodeTradeCpi,
+  encodeCloseSlab,
+  encodeUpdateAdmin,
+} from "../src/abi/instructions.js";
+import { decodeInstruction, decodeBase58, decodeTradeResult, TRADE_RESULT_LEN } from "../src/abi/decode.js";
+import { parseLogTree, findFailure, failedInnerIndex, formatLogTree, summarizeCu } from "../src/runtime/logs.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing instruction/log decoding...\n");
+
+// Round-trip encoders through the decoder
+{
+  const d = decodeInstruction(encodeDepositCollateral({ userIdx: 7, amount: "1000000" }));
+  assert(d.name === "DepositCollateral", "deposit name");
+  assert(d.fields.userIdx === "7", "deposit userIdx");
+  assert(d.fields.amount === "1000000", "deposit amount");
+  assert(d.error === undefined, "deposit no error");
+  assert(d.accountNames[1] === "slab", "deposit account names");
+  console.log("✓ decode DepositCollateral");
+}
+
+{
+  const d = decodeInstruction(encodeTradeCpi({ lpIdx: 0, userIdx: 1, size: "-12345" }));
+  assert(d.name === "TradeCpi", "trade name");
+  assert(d.fields.size === "-12345", "negative i128 size");
+  console.log("✓ decode TradeCpi (negative size)");
+}
+
+{
+  const admin = "11111111111111111111111111111112";
+  const d = decodeInstruction(encodeUpdateAdmin({ newAdmin: admin }));
+  assert(d.fields.newAdmin === admin, "pubkey field");
+  assert(decodeInstruction(encodeCloseSlab()).name === "CloseSlab", "zero-payload variant");
+  console.log("✓ decode UpdateAdmin / CloseSlab");
+}
+
+// Malformed data mirrors the on-chain decoder errors
+{
+  const full = encodeDepositCollateral({ userIdx: 1, amount: "5" });
+  assert(decodeInstruction(full.subarray(0, full.length - 1)).error!.startsWith("TruncatedData"), "truncated");
+  assert(decodeInstruction(Buffer.concat([full, Buffer.from([0])])).error!.startsWith("TrailingBytes"), "trailing");
+  assert(decodeInstruction(Buffer.from([0xff])).error!.includes("unknown tag"), "unknown tag");
+  console.log("✓ decode errors");
+}
+
+// Base58
+{
+  assert(decodeBase58("1").equals(Buffer.from([0])), "base58 leading zero");
+  assert(decodeBase58("2g").equals(Buffer.from([0x61])), "base58 'a'");
+  console.log("✓ decodeBase58");
+}
+
+// CPI tree
+{
+  const logs = [
+    "Program Perc invoke [1]",
+    "Program log: CRANK_STATS",
+    "Program log: 0xc8a4c, 0x2, 0x0, 0x40, 0x3e8",
+    "Program Match invoke [2]",
+    "Program log: matcher says no",
+    "Program Match consumed 1200 of 190000 compute units",
+    "Program Match failed: custom program error: 0x1",
+    "Program Perc consumed 9000 of 200000 compute units",
+    "Program Perc failed: custom program error: 0x1",
+  ];
+  const tree = parseLogTree(logs);
+  assert(tree.length === 1, "one root");
+  assert(tree[0].children.length === 1, "one child");
+  assert(tree[0].unitsConsumed === 9000, "root CU");
+  assert(tree[0].events[0].name === "CrankStats", "event name");
+  assert(tree[0].events[0].fields.liquidations === "2", "event field");
+  assert(tree[0].events[0].fields.insuranceLow === "1000", "event hex field");
+  const failure = findFailure(tree);
+  assert(failure !== null && failure.programId === "Match", "deepest failure");
+  assert(failedInnerIndex(tree[0]) === 0, "failing inner ix");
+  console.log("✓ parseLogTree / findFailure");
+
+  // CU attributed to the program that spent it, not its caller
+  const cu = summarizeCu(tree);
+  assert(cu[0].programId === "Perc" && cu[0].selfUnits === 7800, "caller self CU excludes CPI");
+  assert(cu[1].programId === "Match" && cu[1].selfUnits === 1200, "callee CU");
+
+  // The decorator sees which program failed, so only percolator's are decoded
+  const rendered = formatLogTree(tree, undefined, (e, p) => (p === "Perc" ? `${e} (decoded)` : e));
+  assert(rendered.some((l) => l.startsWith("Perc") && l.endsWith("0x1 (decoded)")), "error decorator");
+  assert(rendered.some((l) => l.trim().startsWith("Match") && l.endsWith("0x1")), "foreign error left raw");
+  console.log("✓ summarizeCu / formatLogTree error decoding");
+}
+
+// Failing CPI position counts every earlier inner ix, nested ones included
+{
+  const tree = parseLogTree([
+    "Program Perc invoke [1]",
+    "Program Token invoke [2]",
+    "Program Token success",
+    "Program Match invoke [2]",
+    "Program Sys invoke [3]",
+    "Program Sys success",
+    "Program Match failed: custom program error: 0x3",
+    "Program Perc failed: custom program error: 0x3",
+    "Program Other invoke [1]",
+    "Program Other failed: invalid account data",
+  ]);
+  assert(failedInnerIndex(tree[0]) === 1, "second inner ix failed");
+  assert(failedInnerIndex(tree[1]) === null, "top-level failure has no inner index");
+  console.log("✓ failedInnerIndex");
+}
+
+// Sweep summary event, with a negative funding index delta logged as u64 bits
+{
+  const tree = parseLogTree([
+    "Program Perc invoke [1]",
+    "Program log: SWEEP_SUMMARY",
+    "Program log: 0x5ee9, 0x1f4, 0xfffffffffffffc18, 0x2710, 0x3",
+    "Program Perc success",
+  ]);
+  const ev = tree[0].events[0];
+  assert(ev.name === "SweepSummary", "sweep summary name");
+  assert(ev.fields.feeRevenue === "500", "sweep fee revenue");
+  assert(ev.fields.fundingIndexDelta === "-1000", "sweep funding delta is signed");
+  assert(ev.fields.volume === "10000", "sweep volume");
+  assert(ev.fields.liquidations === "3", "sweep liquidations");
+  console.log("✓ SweepSummary event");
+}
+
+// Return data is recorded on the invocation that finished with it
+{
+  const tree = parseLogTree([
+    "Program Perc invoke [1]",
+    "Program Match invoke [2]",
+    "Program return: Match bWF0Y2g=",
+    "Program Match success",
+    "Program return: Perc cGVyYw==",
+    "Program Perc success",
+    "Program Perc invoke [1]",
+    "Program Perc success",
+  ]);
+  assert(tree[0].children[0].returnData?.programId === "Match", "callee return data");
+  assert(tree[0].returnData?.programId === "Perc", "caller return data");
+  assert(tree[0].returnData?.data === "cGVyYw==", "return data payload");
+  assert(tree[1].returnData === undefined, "no return data");
+  console.log("✓ return data per invocation");
+}
+
+// Trade return data layout (percolator-prog trade_result)
+{
+  const buf = Buffer.alloc(TRADE_RESULT_LEN);
+  buf.writeUInt32LE(1, 0);
+  buf.writeUInt16LE(3, 4);
+  buf.writeUInt16LE(7, 6);
+  buf.writeBigUInt64LE(138_500_000n, 8);
+  buf.writeBigInt64LE(-42n, 16); // i128 low half
+  buf.writeBigInt64LE(-1n, 24); // sign extension
+  buf.writeBigUInt64LE(5n, 32);
+  buf.writeBigInt64LE(-142n, 48);
+  buf.writeBigInt64LE(-1n, 56);
+  buf.writeBigUInt64LE(137_000_000n, 64);
+  buf.writeBigUInt64LE(138_000_000n, 72);
+  buf.writeBigUInt64LE(1_000_000n, 80);
+  const r = decodeTradeResult(buf);
+  assert(r !== null, "trade result decodes");
+  assert(r!.lpIdx === 3 && r!.userIdx === 7, "trade result indices");
+  assert(r!.execPriceE6 === 138_500_000n, "trade result exec price");
+  assert(r!.execSize === -42n, "trade result signed size");
+  assert(r!.fee === 5n, "trade result fee");
+  assert(r!.userPosition === -142n, "trade result position");
+  assert(r!.userEntryPriceE6 === 137_000_000n, "trade result entry");
+  assert(r!.oraclePriceE6 === 138_000_000n, "trade result oracle");
+  assert(r!.userCapital === 1_000_000n, "trade result capital");
+
+  assert(decodeTradeResult(buf.subarray(0, TRADE_RESULT_LEN - 1)) === null, "short return data");
+  const other = Buffer.from(buf);
+  other.writeUInt32LE(2, 0);
+  assert(decodeTradeResult(other) === null, "unknown version");
+  console.log("✓ decodeTradeResult");
+}
+
+console.log("\n✅ All tests passed!");
@@ -0,0 +1,175 @@
+import { readFileSync } from "node:fs";
+import {
+  PERCOLATOR_ERRORS,
+  parseErrorFromLogs,
+  parseErrorFromTxErr,
+  parseRentFailure,
+  describeFailure,
+} from "../src/abi/errors.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing error decoding...\n");
+
+const PERC = "Perco1ator111111111111111111111111111111111";
+const TOKEN = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const BUDGET = "ComputeBudget111111111111111111111111111111";
+
+// Table must be contiguous from 0 (mirrors PercolatorError discriminants)
+{
+  const codes = Object.keys(PERCOLATOR_ERRORS).map(Number).sort((a, b) => a - b);
+  codes.forEach((c, i) => assert(c === i, `error table gap at ${i}`));
+  console.log("✓ error table contiguous");
+}
+
+// Table must match the program's PercolatorError variants in declaration
+// order (the enum has no explicit discriminants, so order is the code)
+{
+  const src = readFileSync(new URL("../../prog/src/percolator.rs", import.meta.url), "utf-8");
+  const body = /pub enum PercolatorError \{([^}]*)\}/.exec(src);
+  assert(body !== null, "PercolatorError enum found in prog/src/percolator.rs");
+  const variants = body![1]
+    .split("\n")
+    .map((l) => l.replace(/\/\/.*$/, "").trim())
+    .filter((l) => l !== "")
+    .map((l) => l.replace(/,$/, ""));
+  variants.forEach((v) => assert(/^[A-Za-z]\w*$/.test(v), `plain unit variant: ${v}`));
+  assert(
+    Object.keys(PERCOLATOR_ERRORS).length === variants.length,
+    `table has ${Object.keys(PERCOLATOR_ERRORS).length} codes, enum has ${variants.length}`
+  );
+  variants.forEach((v, code) =>
+    assert(PERCOLATOR_ERRORS[code].name === v, `code ${code}: table ${PERCOLATOR_ERRORS[code].name} != enum ${v}`)
+  );
+  console.log(`✓ error table matches PercolatorError (${variants.length} codes)`);
+}
+
+// Log line
+{
+  const parsed = parseErrorFromLogs([
+    `Program ${PERC} invoke [1]`,
+    `Program ${PERC} failed: custom program error: 0xe`,
+  ], PERC);
+  assert(parsed !== null, "parsed from logs");
+  assert(parsed!.code === 14, "code from logs");
+  assert(parsed!.name === "EngineUndercollateralized", "name from logs");
+  assert(parsed!.hint !== undefined, "hint from logs");
+
+  // A CPI'd program's error is not a percolator error, even though the
+  // runtime repeats it on the percolator frame
+  const cpi = [
+    `Program ${PERC} invoke [1]`,
+    `Program ${TOKEN} invoke [2]`,
+    "Program log: Error: insufficient funds",
+    `Program ${TOKEN} failed: custom program error: 0x1`,
+    `Program ${PERC} failed: custom program error: 0x1`,
+  ];
+  assert(parseErrorFromLogs(cpi, PERC) === null, "token error not decoded");
+  assert(
+    parseErrorFromLogs([`Program ${TOKEN} failed: custom program error: 0x1`], PERC) === null,
+    "other program not decoded"
+  );
+  console.log("✓ parseErrorFromLogs");
+}
+
+// TransactionError object
+{
+  const parsed = parseErrorFromTxErr({ InstructionError: [0, { Custom: 28 }] }, PERC, [PERC]);
+  assert(parsed !== null, "parsed from tx err");
+  assert(parsed!.name === "TrailingBytes", "name from tx err");
+
+  // Index is resolved against the instruction list (compute budget first)
+  const shifted = parseErrorFromTxErr({ InstructionError: [1, { Custom: 28 }] }, PERC, [BUDGET, PERC]);
+  assert(shifted?.name === "TrailingBytes", "shifted index");
+  assert(
+    parseErrorFromTxErr({ InstructionError: [0, { Custom: 1 }] }, PERC, [BUDGET, PERC]) === null,
+    "budget ix error not decoded"
+  );
+  assert(
+    parseErrorFromTxErr({ InstructionError: [1, { Custom: 1 }] }, PERC, [PERC, TOKEN]) === null,
+    "token ix error not decoded"
+  );
+  console.log("✓ parseErrorFromTxErr (object)");
+}
+
+// SendTransactionError message
+{
+  const parsed = parseErrorFromTxErr(
+    "Simulation failed. Message: Transaction simulation failed: Error processing Instruction 0: custom program error: 0x1d.",
+    PERC,
+    [PERC]
+  );
+  assert(parsed !== null, "parsed from message");
+  assert(parsed!.name === "MissingAccount", "name from message");
+  console.log("✓ parseErrorFromTxErr (message)");
+}
+
+// Unknown / non-custom errors
+{
+  assert(
+    parseErrorFromTxErr({ InstructionError: [0, { Custom: 999 }] }, PERC, [PERC])!.name === "Unknown(999)",
+    "unknown code"
+  );
+  assert(parseErrorFromTxErr("AccountNotFound", PERC, [PERC]) === null, "non-custom error");
+  assert(parseErrorFromTxErr(null, PERC, [PERC]) === null, "null error");
+  console.log("✓ unknown errors");
+}
+
+// Runtime rent / fee failures are not program errors
+{
+  assert(
+    parseRentFailure([], { InsufficientFundsForRent: { account_index: 1 } })!.name === "InsufficientFundsForRent",
+    "rent tx error"
+  );
+  assert(parseRentFailure([], "InsufficientFundsForFee")!.name === "InsufficientFundsForFee", "fee error");
+  assert(
+    parseRentFailure(["Transfer: insufficient lamports 10, need 7000000"], "failed")!.name === "InsufficientLamports",
+    "system program log"
+  );
+  assert(
+    parseRentFailure([], "Attempt to debit an account but found no record of a prior credit.")!.name === "PayerNotFunded",
+    "unfunded payer"
+  );
+  assert(parseRentFailure([], { InstructionError: [0, { Custom: 1 }] }) === null, "program error is not rent");
+  console.log("✓ parseRentFailure");
+}
+
+// A System Program transfer out of lamports inside a percolator ix, as the
+// runtime logs it: the custom 0x1 is the system error, not InvalidVersion
+{
+  const SYSTEM = "11111111111111111111111111111111";
+  const logs = [
+    `Program ${BUDGET} invoke [1]`,
+    `Program ${BUDGET} success`,
+    `Program ${PERC} invoke [1]`,
+    `Program ${SYSTEM} invoke [2]`,
+    "Transfer: insufficient lamports 10, need 7000000",
+    `Program ${SYSTEM} failed: custom program error: 0x1`,
+    `Program ${PERC} failed: custom program error: 0x1`,
+  ];
+  const txErr = { InstructionError: [1, { Custom: 1 }] };
+  const failure = describeFailure(logs, txErr, PERC, [BUDGET, PERC]);
+  assert(failure.err === "InsufficientLamports", `rent failure wins, got ${failure.err}`);
+  assert(failure.hint!.includes("rent-check"), "rent hint");
+
+  const program = describeFailure(
+    [`Program ${PERC} invoke [1]`, `Program ${PERC} failed: custom program error: 0xe`],
+    { InstructionError: [1, { Custom: 14 }] },
+    PERC,
+    [BUDGET, PERC]
+  );
+  assert(program.err === "EngineUndercollateralized (0xe)", "program error still decoded");
+
+  const token = describeFailure(
+    [`Program ${TOKEN} failed: custom program error: 0x1`],
+    { InstructionError: [1, { Custom: 1 }] },
+    PERC,
+    [BUDGET, TOKEN]
+  );
+  assert(token.err === JSON.stringify({ InstructionError: [1, { Custom: 1 }] }), "foreign error kept raw");
+  console.log("✓ describeFailure");
+}
+
+console.log("\n✅ All tests passed!");
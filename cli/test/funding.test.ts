@@ -0,0 +1,63 @@
+import {
+  inventoryFundingBpsPerSlot,
+  fundingIndexDelta,
+  fundingPayment,
+  predictFunding,
+} from "../src/runtime/funding.js";
+import { FundingParams } from "../src/solana/slab.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing funding prediction...\n");
+
+// Program defaults (DEFAULT_FUNDING_*)
+const defaults: FundingParams = {
+  horizonSlots: 500n,
+  kBps: 100n,
+  invScaleNotionalE6: 1_000_000_000_000n,
+  maxPremiumBps: 500n,
+  maxBpsPerSlot: 5n,
+};
+const price = 100_000_000n;
+
+{
+  assert(inventoryFundingBpsPerSlot(0n, price, defaults) === 0n, "flat LP => no funding");
+  // notional 5e12 -> premium 500 bps -> 1 bps/slot over 500 slots
+  assert(inventoryFundingBpsPerSlot(50_000_000_000n, price, defaults) === 1n, "LP long => longs pay");
+  assert(inventoryFundingBpsPerSlot(-1_000_000_000_000n, price, defaults) === -1n, "premium capped, LP short");
+  // Below one bps/slot truncates to zero like i64 division
+  assert(inventoryFundingBpsPerSlot(25_000_000_000n, price, defaults) === 0n, "truncates toward zero");
+
+  const short = { ...defaults, horizonSlots: 10n };
+  assert(inventoryFundingBpsPerSlot(25_000_000_000n, price, short) === 5n, "policy clamp");
+  const odd = { ...defaults, horizonSlots: 2n, maxBpsPerSlot: 100n };
+  // premium 7 bps / 2 slots -> 3 (and -3, not -4)
+  assert(inventoryFundingBpsPerSlot(700_000_000n, price, odd) === 3n, "positive truncation");
+  assert(inventoryFundingBpsPerSlot(-700_000_000n, price, odd) === -3n, "negative truncation");
+  console.log("✓ inventoryFundingBpsPerSlot");
+}
+
+{
+  assert(fundingIndexDelta(price, 1n, 10n) === 100_000n, "index delta");
+  assert(fundingIndexDelta(price, -1n, 10n) === -100_000n, "negative index delta");
+
+  // Payers round up, receivers truncate
+  assert(fundingPayment(3n, 0n, 100_000n) === 1n, "payer rounds up");
+  assert(fundingPayment(-3n, 0n, 100_000n) === 0n, "receiver truncates");
+  assert(fundingPayment(25n, 0n, 100_000n) === 3n, "payer 2.5 -> 3");
+  assert(fundingPayment(25n, 100_000n, 100_000n) === 0n, "already settled");
+  console.log("✓ fundingIndexDelta / fundingPayment");
+}
+
+{
+  const p = predictFunding(50_000_000_000n, price, defaults, 1_000n, 90n, 100n);
+  assert(p.rateBpsPerSlot === 1n && p.dt === 10n, "rate and gap");
+  assert(p.nextIndex === 101_000n, "next index");
+  const stale = predictFunding(50_000_000_000n, price, defaults, 0n, 100n, 90n);
+  assert(stale.dt === 0n && stale.indexDelta === 0n, "no accrual before last funding slot");
+  console.log("✓ predictFunding");
+}
+
+console.log("\n✅ All tests passed!");
@@ -0,0 +1,77 @@
+import { markPnl, equityMtm, marginRequired, previewFill } from "../src/runtime/margin.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing margin math...\n");
+
+const params = {
+  maintenanceMarginBps: 500n,
+  initialMarginBps: 1000n,
+  tradingFeeBps: 10n,
+};
+
+// mark_pnl truncates toward zero like Rust i128 division
+{
+  assert(markPnl(1_000_000n, 100_000_000n, 110_000_000n) === 10_000_000n, "long profit");
+  assert(markPnl(-1_000_000n, 100_000_000n, 110_000_000n) === -10_000_000n, "short loss");
+  assert(markPnl(3n, 0n, 1n) === 0n, "truncate positive");
+  assert(markPnl(-3n, 0n, 1n) === 0n, "truncate negative toward zero");
+  assert(markPnl(0n, 1n, 2n) === 0n, "flat");
+  console.log("✓ markPnl");
+}
+
+{
+  const a = { capital: 100n, pnl: -50n, positionSize: 1_000_000n, entryPrice: 1_000_000n };
+  assert(equityMtm(a, 1_000_000n) === 50n, "equity");
+  assert(equityMtm(a, 0n) === 0n, "equity floored at zero");
+  assert(marginRequired(-10_000_000n, 1_000_000n, 500n) === 500_000n, "margin required uses |pos|");
+  console.log("✓ equityMtm / marginRequired");
+}
+
+// Opening a position: fee charged, entry = exec, margin checked at oracle
+{
+  const a = { capital: 1_000_000n, pnl: 0n, positionSize: 0n, entryPrice: 0n };
+  // 10 units @ 1.0 => notional 10_000_000, fee 10_000, MM 500_000, IM 1_000_000
+  const p = previewFill(a, params, 10_000_000n, 1_000_000n, 1_000_000n);
+  assert(p.newPosition === 10_000_000n, "position");
+  assert(p.newEntryPrice === 1_000_000n, "entry");
+  assert(p.fee === 10_000n, "fee");
+  assert(p.equity === 990_000n, "equity after fee");
+  assert(!p.belowMaintenance, "above maintenance");
+  assert(p.belowInitial, "below initial");
+  assert(p.freeCollateral === -10_000n, "free collateral shortfall");
+  console.log("✓ previewFill open");
+}
+
+// Too large: rejected
+{
+  const a = { capital: 100_000n, pnl: 0n, positionSize: 0n, entryPrice: 0n };
+  const p = previewFill(a, params, 10_000_000n, 1_000_000n, 1_000_000n);
+  assert(p.belowMaintenance, "below maintenance");
+  console.log("✓ previewFill reject");
+}
+
+// Reducing a position realizes PnL and keeps entry
+{
+  const a = { capital: 1_000_000n, pnl: 0n, positionSize: 10_000_000n, entryPrice: 1_000_000n };
+  const p = previewFill(a, params, -5_000_000n, 1_100_000n, 1_100_000n, false);
+  assert(p.newPosition === 5_000_000n, "reduced position");
+  assert(p.newEntryPrice === 1_000_000n, "entry unchanged on reduce");
+  assert(p.newPnl === 500_000n, "realized pnl");
+  console.log("✓ previewFill reduce");
+}
+
+// Flip resets entry to exec price; extend averages
+{
+  const a = { capital: 10_000_000n, pnl: 0n, positionSize: 1_000_000n, entryPrice: 1_000_000n };
+  const flip = previewFill(a, params, -3_000_000n, 2_000_000n, 2_000_000n, false);
+  assert(flip.newPosition === -2_000_000n, "flipped");
+  assert(flip.newEntryPrice === 2_000_000n, "entry reset on flip");
+  const ext = previewFill(a, params, 1_000_000n, 3_000_000n, 3_000_000n, false);
+  assert(ext.newEntryPrice === 2_000_000n, "weighted entry");
+  console.log("✓ previewFill flip / extend");
+}
+
+console.log("\n✅ All tests passed!");
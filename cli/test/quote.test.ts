@@ -0,0 +1,49 @@
+import { computePassiveQuote, quoteFill } from "../src/runtime/quote.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing quote math...\n");
+
+// Passive matcher rounds against the taker
+{
+  const { bid, ask } = computePassiveQuote(1_000_001n, 50n);
+  assert(bid === 995_000n, "bid rounds down");
+  assert(ask === 1_005_002n, "ask rounds up");
+  console.log("✓ computePassiveQuote rounding");
+}
+
+{
+  const q = quoteFill(1_000_000n, 10_000_000n, 50n, 10n);
+  assert(q.side === "buy", "buy side");
+  assert(q.execPrice === 1_005_000n, "buy at ask");
+  assert(q.notional === 10_050_000n, "notional");
+  assert(q.fee === 10_050n, "fee");
+  assert(q.slippageBps === 50, "slippage");
+  assert(q.effectivePrice === 1_006_005n, "fee-inclusive price");
+  console.log("✓ quoteFill buy");
+}
+
+{
+  const q = quoteFill(1_000_000n, -10_000_000n, 50n, 10n);
+  assert(q.side === "sell", "sell side");
+  assert(q.execPrice === 995_000n, "sell at bid");
+  assert(q.fee === 9_950n, "fee");
+  assert(q.slippageBps === 50, "slippage");
+  assert(q.effectivePrice === 994_005n, "fee-inclusive price");
+  console.log("✓ quoteFill sell");
+}
+
+{
+  let threw = false;
+  try {
+    quoteFill(1_000_000n, 0n, 50n, 10n);
+  } catch {
+    threw = true;
+  }
+  assert(threw, "zero size rejected");
+  console.log("✓ quoteFill rejects zero size");
+}
+
+console.log("\n✅ All tests passed!");
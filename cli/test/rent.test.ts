@@ -0,0 +1,23 @@
+import { summarizeRent } from "../src/runtime/rent.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing rent preflight...\n");
+
+{
+  const items = [
+    { label: "slab", space: 100, lamports: 1_000_000 },
+    { label: "matcher context", space: 10, lamports: 200_000 },
+  ];
+  const ok = summarizeRent(items, 2_000_000, 5_000);
+  assert(ok.required === 1_205_000, "required includes fee buffer");
+  assert(ok.shortfall === 0, "covered");
+
+  const short = summarizeRent(items, 1_200_000, 5_000);
+  assert(short.shortfall === 5_000, "shortfall");
+  console.log("✓ summarizeRent");
+}
+
+console.log("\n✅ All tests passed!");
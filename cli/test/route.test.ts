@@ -0,0 +1,65 @@
+import { lpCapacity, planRoute } from "../src/runtime/route.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing route planning...\n");
+
+// 1.0 equity at 5% MM and price 1.0: the engine needs equity > requirement,
+// so |pos| stops one unit short of 20.0
+{
+  const flat = { capital: 1_000_000n, pnl: 0n, positionSize: 0n, entryPrice: 0n };
+  assert(lpCapacity(flat, 500n, 1_000_000n, 1n) === 19_999_999n, "flat LP capacity");
+
+  const long = { capital: 1_000_000n, pnl: 0n, positionSize: 4_000_000n, entryPrice: 1_000_000n };
+  // Taker buys => LP sells => reduces its long first
+  assert(lpCapacity(long, 500n, 1_000_000n, 1n) === 23_999_999n, "reducing LP capacity");
+  // Taker sells => LP buys more => only headroom
+  assert(lpCapacity(long, 500n, 1_000_000n, -1n) === 15_999_999n, "extending LP capacity");
+
+  const maxed = { capital: 100_000n, pnl: 0n, positionSize: -4_000_000n, entryPrice: 1_000_000n };
+  assert(lpCapacity(maxed, 500n, 1_000_000n, 1n) === 0n, "no headroom");
+  // Under maintenance: a partial reduction would still fail the LP check
+  assert(lpCapacity(maxed, 500n, 1_000_000n, -1n) === 0n, "under maintenance");
+
+  // Exactly at the requirement is rejected, like execute_trade's strict check
+  const edge = { capital: 50_000n, pnl: 0n, positionSize: 0n, entryPrice: 0n };
+  assert(lpCapacity(edge, 500n, 1_000_000n, 1n) === 999_999n, "strictly above maintenance");
+  console.log("✓ lpCapacity");
+}
+
+// Best price first, spill over on capacity
+{
+  const plan = planRoute(
+    [
+      { slab: "A", lpIdx: 0, execPrice: 1_010_000n, capacity: 100n },
+      { slab: "B", lpIdx: 1, execPrice: 1_005_000n, capacity: 30n },
+      { slab: "C", lpIdx: 0, execPrice: 1_005_000n, capacity: 50n },
+    ],
+    100n
+  );
+  assert(plan.legs.length === 3, "three legs");
+  assert(plan.legs[0].slab === "C" && plan.legs[0].size === 50n, "tie broken by capacity");
+  assert(plan.legs[1].slab === "B" && plan.legs[1].size === 30n, "second best");
+  assert(plan.legs[2].slab === "A" && plan.legs[2].size === 20n, "remainder");
+  assert(plan.unfilled === 0n, "fully filled");
+  assert(plan.vwap === 1_006_000n, "vwap");
+  console.log("✓ planRoute buy split");
+}
+
+{
+  const plan = planRoute(
+    [
+      { slab: "A", lpIdx: 0, execPrice: 990_000n, capacity: 10n },
+      { slab: "B", lpIdx: 0, execPrice: 995_000n, capacity: 10n },
+    ],
+    -25n
+  );
+  assert(plan.legs[0].slab === "B", "sell prefers highest bid");
+  assert(plan.legs[0].size === -10n, "sell legs are negative");
+  assert(plan.filled === 20n && plan.unfilled === 5n, "partial fill");
+  console.log("✓ planRoute sell / unfilled");
+}
+
+console.log("\n✅ All tests passed!");
@@ -0,0 +1,92 @@
+import {
+  parseShockBps,
+  shockPrice,
+  stressAccount,
+  summarizeStress,
+  sweepStart,
+} from "../src/runtime/stress.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing stress math...\n");
+
+const params = {
+  maintenanceMarginBps: 500n,
+  initialMarginBps: 1000n,
+  tradingFeeBps: 10n,
+};
+
+{
+  assert(parseShockBps("-20%") === -2000n, "-20%");
+  assert(parseShockBps("+5%") === 500n, "+5%");
+  assert(parseShockBps("-12.5") === -1250n, "fractional, no % sign");
+  assert(parseShockBps("0") === 0n, "zero");
+  for (const bad of ["-100%", "-150%", "abc", "1.234%", ""]) {
+    let threw = false;
+    try {
+      parseShockBps(bad);
+    } catch {
+      threw = true;
+    }
+    assert(threw, `rejects ${JSON.stringify(bad)}`);
+  }
+  console.log("✓ parseShockBps");
+}
+
+{
+  assert(shockPrice(100_000_000n, -2000n) === 80_000_000n, "down 20%");
+  assert(shockPrice(100_000_000n, 500n) === 105_000_000n, "up 5%");
+  assert(shockPrice(1n, -9999n) === 1n, "floored at 1");
+  console.log("✓ shockPrice");
+}
+
+{
+  // 10 units long from 1.0, capital 1.0: at 0.8 equity 1.0 - 2.0 => 0
+  const long = { slab: "A", idx: 0, capital: 1_000_000n, pnl: 0n, positionSize: 10_000_000n, entryPrice: 1_000_000n };
+  const r = stressAccount(long, params, 1_000_000n, 800_000n);
+  assert(r.equityBefore === 1_000_000n, "equity before");
+  assert(r.equity === 0n, "equity floored at zero");
+  assert(r.maintenanceRequired === 400_000n, "maintenance at shocked price");
+  assert(r.shortfall === 400_000n && r.liquidatable, "long liquidated on the way down");
+
+  // The mirror short gains from the same move
+  const short = { ...long, idx: 1, positionSize: -10_000_000n };
+  const s = stressAccount(short, params, 1_000_000n, 800_000n);
+  assert(s.equity === 3_000_000n && !s.liquidatable && s.shortfall === 0n, "short profits");
+
+  // Flat accounts are never liquidatable, even with no equity
+  const flat = { ...long, idx: 2, capital: 0n, positionSize: 0n };
+  assert(!stressAccount(flat, params, 1_000_000n, 800_000n).liquidatable, "flat not liquidatable");
+
+  // Exactly at maintenance is liquidatable (equity must be strictly above)
+  const edge = { ...long, idx: 3, capital: 2_400_000n };
+  const e = stressAccount(edge, params, 1_000_000n, 800_000n);
+  assert(e.equity === e.maintenanceRequired && e.liquidatable, "at maintenance liquidates");
+  console.log("✓ stressAccount");
+}
+
+{
+  const mk = (idx: number, capital: bigint) =>
+    stressAccount(
+      { slab: "A", idx, capital, pnl: 0n, positionSize: 10_000_000n, entryPrice: 1_000_000n },
+      params,
+      1_000_000n,
+      800_000n
+    );
+  // equity 0.2, 0 and 3.0 (healthy): liquidations follow index order, not depth
+  const report = summarizeStress([mk(0, 2_200_000n), mk(1, 1_000_000n), mk(2, 5_000_000n)]);
+  assert(report.liquidations.map((r) => r.idx).join() === "0,1", "index order");
+  assert(report.equityAfter === 200_000n + 0n + 3_000_000n, "total equity");
+  assert(report.totalShortfall === 200_000n + 400_000n, "total shortfall");
+  assert(summarizeStress([]).liquidations.length === 0, "empty");
+
+  // The sweep starts at the current step's window and wraps
+  assert(sweepStart(0) === 0 && sweepStart(15) === 3840, "window starts");
+  const wrapped = summarizeStress([mk(5, 0n), mk(3900, 0n), mk(300, 0n)], new Map([["A", sweepStart(15)]]));
+  assert(wrapped.liquidations.map((r) => r.idx).join() === "3900,5,300", "sweep wraps from step 15");
+  console.log("✓ summarizeStress");
+}
+
+console.log("\n✅ All tests passed!");
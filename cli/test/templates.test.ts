@@ -0,0 +1,46 @@
+import { resolveMarketParams, MARKET_TEMPLATES } from "../src/runtime/templates.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+function throws(fn: () => unknown, match: string): boolean {
+  try {
+    fn();
+  } catch (e) {
+    return e instanceof Error && e.message.includes(match);
+  }
+  return false;
+}
+
+console.log("Testing market templates...\n");
+
+{
+  const p = resolveMarketParams("devnet", {});
+  assert(p.maintenanceMarginBps === "500" && p.initialMarginBps === "1000", "devnet margins");
+  for (const [name, t] of Object.entries(MARKET_TEMPLATES)) {
+    assert(BigInt(t.initialMarginBps) > BigInt(t.maintenanceMarginBps), `${name}: IM above MM`);
+  }
+  console.log("✓ templates");
+}
+
+{
+  const p = resolveMarketParams("major", { tradingFeeBps: "7", initialMarginBps: undefined });
+  assert(p.tradingFeeBps === "7", "explicit flag overrides template");
+  assert(p.initialMarginBps === MARKET_TEMPLATES.major.initialMarginBps, "unset flag keeps template");
+  assert(MARKET_TEMPLATES.major.tradingFeeBps === "5", "template not mutated");
+  console.log("✓ overrides");
+}
+
+{
+  assert(throws(() => resolveMarketParams("btc-perp", {}), 'Unknown market template "btc-perp"'), "unknown template");
+  assert(
+    throws(() => resolveMarketParams(undefined, { tradingFeeBps: "10" }), "warmupPeriodSlots"),
+    "missing params listed without a template"
+  );
+  const full = resolveMarketParams(undefined, { ...MARKET_TEMPLATES.devnet });
+  assert(full.liquidationFeeCap === "1000000000", "all explicit, no template");
+  console.log("✓ validation");
+}
+
+console.log("\n✅ All tests passed!");
@@ -11,6 +11,7 @@ import {
   validateI128,
   validateBps,
   validateU16,
+  validateTradeLeg,
   ValidationError,
 } from "../src/validation.js";
 
@@ -140,6 +141,22 @@ console.log("Testing validation functions...\n");
   console.log("✓ validateBps");
 }
 
+// validateTradeLeg tests
+{
+  const slab = "11111111111111111111111111111111";
+  const leg = validateTradeLeg(`${slab}:0:3:-1000`, "--leg");
+  assert(leg.slab.toBase58() === slab, "parses slab");
+  assert(leg.lpIdx === 0, "parses lp idx");
+  assert(leg.userIdx === 3, "parses user idx");
+  assert(leg.size === -1000n, "parses signed size");
+
+  assertThrows(() => validateTradeLeg(`${slab}:0:3`, "--leg"), "must be", "rejects missing field");
+  assertThrows(() => validateTradeLeg(`${slab}:0:3:0`, "--leg"), "non-zero", "rejects zero size");
+  assertThrows(() => validateTradeLeg("not-a-key:0:3:1", "--leg"), "--leg", "rejects bad slab");
+
+  console.log("✓ validateTradeLeg");
+}
+
 // ValidationError tests
 {
   const err = new ValidationError("--amount", "must be positive");
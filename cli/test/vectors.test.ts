@@ -0,0 +1,65 @@
+import { readFileSync } from "node:fs";
+import { markPnl, previewFill, MarginAccount } from "../src/runtime/margin.js";
+
+function assert(cond: boolean, msg: string): void {
+  if (!cond) throw new Error(`FAIL: ${msg}`);
+}
+
+console.log("Testing margin math against shared engine vectors...\n");
+
+// Same file the engine is checked against (tests/vectors.rs)
+const text = readFileSync(new URL("../../tests/vectors/margin.txt", import.meta.url), "utf-8");
+const lines = text
+  .split("\n")
+  .map((l, i) => ({ n: i + 1, f: l.trim().split(/\s+/) }))
+  .filter(({ f }) => f[0] !== "" && !f[0].startsWith("#"));
+
+const params = {
+  maintenanceMarginBps: 500n,
+  initialMarginBps: 1000n,
+  tradingFeeBps: 10n,
+};
+
+{
+  const cases = lines.filter(({ f }) => f[0] === "mark_pnl");
+  assert(cases.length > 0, "mark_pnl vectors present");
+  for (const { n, f } of cases) {
+    const got = markPnl(BigInt(f[1]), BigInt(f[2]), BigInt(f[3]));
+    assert(got === BigInt(f[4]), `margin.txt:${n} mark_pnl ${got} != ${f[4]}`);
+  }
+  console.log(`✓ mark_pnl (${cases.length} vectors)`);
+}
+
+{
+  const cases = lines.filter(({ f }) => f[0] === "trade");
+  assert(cases.length > 0, "trade vectors present");
+  for (const { n, f } of cases) {
+    let acct: MarginAccount = { capital: BigInt(f[1]), pnl: 0n, positionSize: 0n, entryPrice: 0n };
+    const fills = f[2].split(",").map((s) => s.split("@").map(BigInt));
+    let lastOk = true;
+    let equity = 0n;
+    fills.forEach(([size, price], i) => {
+      const p = previewFill(acct, params, size, price, price);
+      const ok = !p.belowMaintenance;
+      if (i + 1 < fills.length) {
+        assert(ok, `margin.txt:${n} fill ${i} rejected`);
+      } else {
+        lastOk = ok;
+      }
+      if (ok) {
+        acct = { capital: acct.capital, pnl: p.newPnl, positionSize: p.newPosition, entryPrice: p.newEntryPrice };
+        equity = p.equity;
+      } else {
+        // Rejected: account unchanged, equity is pre-trade MTM
+        equity = acct.capital + acct.pnl + markPnl(acct.positionSize, acct.entryPrice, price);
+      }
+    });
+    assert(lastOk === (f[6] === "ok"), `margin.txt:${n} accepted`);
+    assert(acct.positionSize === BigInt(f[3]), `margin.txt:${n} position ${acct.positionSize}`);
+    assert(acct.entryPrice === BigInt(f[4]), `margin.txt:${n} entry ${acct.entryPrice}`);
+    assert(equity === BigInt(f[5]), `margin.txt:${n} equity ${equity}`);
+  }
+  console.log(`✓ trade (${cases.length} vectors)`);
+}
+
+console.log("\n✅ All tests passed!");
@@ -0,0 +1,12 @@
+//! Fuzz the instruction decoder with arbitrary bytes.
+//!
+//! Property: `Instruction::decode` never panics, whatever the input.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use percolator_prog::ix::Instruction;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Instruction::decode(data);
+});
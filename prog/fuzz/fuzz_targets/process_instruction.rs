@@ -0,0 +1,141 @@
+//! Fuzz `process_instruction` with arbitrary instruction data and account layouts.
+//!
+//! Properties:
+//! - never panics
+//! - on any error, no lamports and no account data outside a slab's engine
+//!   region were modified: handlers finish every fallible step before writing
+//!   headers, config, dust or token balances. The engine region is exempt; the
+//!   risk engine settles accounts before some checks and relies on the runtime
+//!   rolling back a failed transaction (see tests/fuzzing.rs in the engine)
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use percolator_prog::{
+    constants::{ENGINE_OFF, SLAB_LEN},
+    processor::process_instruction,
+};
+use solana_program::{account_info::AccountInfo, program_pack::Pack, pubkey::Pubkey, sysvar};
+
+const MAX_FUZZ_ACCOUNTS: usize = 12;
+
+#[derive(Arbitrary, Debug)]
+enum KeySel {
+    Program,
+    TokenProgram,
+    ClockSysvar,
+    Slot(u8),
+}
+
+#[derive(Arbitrary, Debug)]
+enum DataSel {
+    Empty,
+    /// Zeroed slab-sized buffer (uninitialized market)
+    Slab,
+    /// Slab-sized buffer with a fuzzed prefix (header/config)
+    SlabPrefix(Vec<u8>),
+    /// spl-token account sized buffer
+    Token(Vec<u8>),
+    Raw(Vec<u8>),
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: KeySel,
+    owner: KeySel,
+    lamports: u64,
+    data: DataSel,
+    is_signer: bool,
+    is_writable: bool,
+    executable: bool,
+}
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    accounts: Vec<FuzzAccount>,
+    data: Vec<u8>,
+}
+
+fn resolve_key(program_id: &Pubkey, sel: &KeySel) -> Pubkey {
+    match sel {
+        KeySel::Program => *program_id,
+        KeySel::TokenProgram => spl_token::ID,
+        KeySel::ClockSysvar => sysvar::clock::ID,
+        KeySel::Slot(n) => Pubkey::new_from_array([*n; 32]),
+    }
+}
+
+fn resolve_data(sel: &DataSel) -> Vec<u8> {
+    match sel {
+        DataSel::Empty => Vec::new(),
+        DataSel::Slab => vec![0u8; SLAB_LEN],
+        DataSel::SlabPrefix(prefix) => {
+            let mut buf = vec![0u8; SLAB_LEN];
+            let n = prefix.len().min(SLAB_LEN);
+            buf[..n].copy_from_slice(&prefix[..n]);
+            buf
+        }
+        DataSel::Token(bytes) => {
+            let mut buf = vec![0u8; spl_token::state::Account::LEN];
+            let n = bytes.len().min(buf.len());
+            buf[..n].copy_from_slice(&bytes[..n]);
+            buf
+        }
+        DataSel::Raw(bytes) => bytes.clone(),
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let program_id = percolator_prog::id();
+
+    let mut keys = Vec::new();
+    let mut owners = Vec::new();
+    let mut lamports = Vec::new();
+    let mut datas = Vec::new();
+    for a in input.accounts.iter().take(MAX_FUZZ_ACCOUNTS) {
+        keys.push(resolve_key(&program_id, &a.key));
+        owners.push(resolve_key(&program_id, &a.owner));
+        lamports.push(a.lamports);
+        datas.push(resolve_data(&a.data));
+    }
+
+    let lamports_before = lamports.clone();
+    let datas_before = datas.clone();
+
+    let result = {
+        let infos: Vec<AccountInfo> = keys
+            .iter()
+            .zip(owners.iter())
+            .zip(lamports.iter_mut())
+            .zip(datas.iter_mut())
+            .zip(input.accounts.iter())
+            .map(|((((key, owner), lamports), data), a)| {
+                AccountInfo::new(
+                    key,
+                    a.is_signer,
+                    a.is_writable,
+                    lamports,
+                    data,
+                    owner,
+                    a.executable,
+                    0,
+                )
+            })
+            .collect();
+        process_instruction(&program_id, &infos, &input.data)
+    };
+
+    if let Err(err) = result {
+        assert_eq!(lamports, lamports_before, "lamports written before {:?}", err);
+        for (i, (after, before)) in datas.iter().zip(datas_before.iter()).enumerate() {
+            let handler_bytes = if after.len() == SLAB_LEN { ENGINE_OFF } else { after.len() };
+            assert!(
+                after[..handler_bytes] == before[..handler_bytes],
+                "account {} data written before {:?}",
+                i,
+                err
+            );
+        }
+    }
+});
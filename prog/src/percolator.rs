@@ -35,6 +35,11 @@ pub mod constants {
     pub const MATCHER_CONTEXT_LEN: usize = 320;
     pub const MATCHER_CALL_TAG: u8 = 0;
     pub const MATCHER_CALL_LEN: usize = 67;
+    pub const TRADE_RESULT_VERSION: u32 = 1;
+    pub const TRADE_RESULT_LEN: usize = 96;
+
+    /// SlabHeader.flags bit: market only accepts trades that shrink the user's position
+    pub const FLAG_REDUCE_ONLY: u8 = 1;
 
     /// Sentinel value for permissionless crank (no caller account required)
     pub const CRANK_NO_CALLER: u16 = u16::MAX;
@@ -302,6 +307,16 @@ pub mod verify {
         expected == provided
     }
 
+    /// Reduce-only check: the user's position may shrink toward zero but not
+    /// grow or flip sides. A zero delta is always allowed.
+    #[inline]
+    pub fn reduce_only_ok(old_pos: i128, delta: i128) -> bool {
+        delta == 0
+            || (old_pos != 0
+                && delta.signum() != old_pos.signum()
+                && delta.unsigned_abs() <= old_pos.unsigned_abs())
+    }
+
     /// Trade size selection for CPI path: must use exec_size from matcher, not requested size.
     /// Returns the size that should be passed to engine.execute_trade.
     #[inline]
@@ -887,6 +902,7 @@ pub mod zc {
 pub mod matcher_abi {
     use solana_program::program_error::ProgramError;
     use crate::constants::MATCHER_ABI_VERSION;
+    use crate::le::{read_u32, read_u64, read_i128};
 
     /// Matcher return flags
     pub const FLAG_VALID: u32 = 1;       // bit0: response is valid
@@ -908,14 +924,15 @@ pub mod matcher_abi {
 
     pub fn read_matcher_return(ctx: &[u8]) -> Result<MatcherReturn, ProgramError> {
         if ctx.len() < 64 { return Err(ProgramError::InvalidAccountData); }
-        let abi_version = u32::from_le_bytes(ctx[0..4].try_into().unwrap());
-        let flags = u32::from_le_bytes(ctx[4..8].try_into().unwrap());
-        let exec_price_e6 = u64::from_le_bytes(ctx[8..16].try_into().unwrap());
-        let exec_size = i128::from_le_bytes(ctx[16..32].try_into().unwrap());
-        let req_id = u64::from_le_bytes(ctx[32..40].try_into().unwrap());
-        let lp_account_id = u64::from_le_bytes(ctx[40..48].try_into().unwrap());
-        let oracle_price_e6 = u64::from_le_bytes(ctx[48..56].try_into().unwrap());
-        let reserved = u64::from_le_bytes(ctx[56..64].try_into().unwrap());
+        let mut cur = &ctx[..64];
+        let abi_version = read_u32(&mut cur)?;
+        let flags = read_u32(&mut cur)?;
+        let exec_price_e6 = read_u64(&mut cur)?;
+        let exec_size = read_i128(&mut cur)?;
+        let req_id = read_u64(&mut cur)?;
+        let lp_account_id = read_u64(&mut cur)?;
+        let oracle_price_e6 = read_u64(&mut cur)?;
+        let reserved = read_u64(&mut cur)?;
 
         Ok(MatcherReturn {
             abi_version, flags, exec_price_e6, exec_size, req_id, lp_account_id, oracle_price_e6, reserved
@@ -957,6 +974,64 @@ pub mod matcher_abi {
     }
 }
 
+/// Fill result published via `set_return_data` by TradeNoCpi / TradeCpi, so
+/// callers (CPI wrappers, clients via simulate) get the outcome without
+/// re-reading the slab.
+pub mod trade_result {
+    use crate::constants::{TRADE_RESULT_VERSION, TRADE_RESULT_LEN};
+    use crate::le::{read_u16, read_u32, read_u64, read_i128, read_u128};
+
+    #[repr(C)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TradeResult {
+        pub version: u32,
+        pub lp_idx: u16,
+        pub user_idx: u16,
+        pub exec_price_e6: u64,
+        pub exec_size: i128,
+        pub fee: u128,
+        pub user_position: i128,
+        pub user_entry_price_e6: u64,
+        pub oracle_price_e6: u64,
+        pub user_capital: u128,
+    }
+
+    impl TradeResult {
+        pub fn encode(&self) -> [u8; TRADE_RESULT_LEN] {
+            let mut out = [0u8; TRADE_RESULT_LEN];
+            out[0..4].copy_from_slice(&self.version.to_le_bytes());
+            out[4..6].copy_from_slice(&self.lp_idx.to_le_bytes());
+            out[6..8].copy_from_slice(&self.user_idx.to_le_bytes());
+            out[8..16].copy_from_slice(&self.exec_price_e6.to_le_bytes());
+            out[16..32].copy_from_slice(&self.exec_size.to_le_bytes());
+            out[32..48].copy_from_slice(&self.fee.to_le_bytes());
+            out[48..64].copy_from_slice(&self.user_position.to_le_bytes());
+            out[64..72].copy_from_slice(&self.user_entry_price_e6.to_le_bytes());
+            out[72..80].copy_from_slice(&self.oracle_price_e6.to_le_bytes());
+            out[80..96].copy_from_slice(&self.user_capital.to_le_bytes());
+            out
+        }
+
+        pub fn decode(data: &[u8]) -> Option<TradeResult> {
+            let mut cur = data.get(..TRADE_RESULT_LEN)?;
+            let version = read_u32(&mut cur).ok()?;
+            if version != TRADE_RESULT_VERSION { return None; }
+            Some(TradeResult {
+                version,
+                lp_idx: read_u16(&mut cur).ok()?,
+                user_idx: read_u16(&mut cur).ok()?,
+                exec_price_e6: read_u64(&mut cur).ok()?,
+                exec_size: read_i128(&mut cur).ok()?,
+                fee: read_u128(&mut cur).ok()?,
+                user_position: read_i128(&mut cur).ok()?,
+                user_entry_price_e6: read_u64(&mut cur).ok()?,
+                oracle_price_e6: read_u64(&mut cur).ok()?,
+                user_capital: read_u128(&mut cur).ok()?,
+            })
+        }
+    }
+}
+
 // 3. mod error
 pub mod error {
     use solana_program::program_error::ProgramError;
@@ -991,6 +1066,14 @@ pub mod error {
         InvalidTokenAccount,
         InvalidTokenProgram,
         InvalidConfigParam,
+        /// Instruction data ended before all fields of the variant were read
+        TruncatedData,
+        /// Instruction data has bytes left over after the last field
+        TrailingBytes,
+        /// Fewer accounts supplied than the instruction requires
+        MissingAccount,
+        /// Market is reduce-only and the trade would grow or flip the user's position
+        MarketReduceOnly,
     }
 
     impl From<PercolatorError> for ProgramError {
@@ -1017,10 +1100,67 @@ pub mod error {
     }
 }
 
+// 3b. mod le - bounds-checked little-endian cursor reads
+pub mod le {
+    use solana_program::{pubkey::Pubkey, program_error::ProgramError};
+    use crate::error::PercolatorError;
+
+    /// Split the next `N` bytes off the cursor, or fail with TruncatedData
+    /// without consuming anything.
+    fn take<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], ProgramError> {
+        if input.len() < N { return Err(PercolatorError::TruncatedData.into()); }
+        let (bytes, rest) = input.split_at(N);
+        *input = rest;
+        Ok(bytes.try_into().unwrap())
+    }
+
+    pub fn read_u8(input: &mut &[u8]) -> Result<u8, ProgramError> {
+        take::<1>(input).map(|b| b[0])
+    }
+
+    pub fn read_u16(input: &mut &[u8]) -> Result<u16, ProgramError> {
+        take(input).map(u16::from_le_bytes)
+    }
+
+    pub fn read_u32(input: &mut &[u8]) -> Result<u32, ProgramError> {
+        take(input).map(u32::from_le_bytes)
+    }
+
+    pub fn read_u64(input: &mut &[u8]) -> Result<u64, ProgramError> {
+        take(input).map(u64::from_le_bytes)
+    }
+
+    pub fn read_i32(input: &mut &[u8]) -> Result<i32, ProgramError> {
+        take(input).map(i32::from_le_bytes)
+    }
+
+    pub fn read_i64(input: &mut &[u8]) -> Result<i64, ProgramError> {
+        take(input).map(i64::from_le_bytes)
+    }
+
+    pub fn read_i128(input: &mut &[u8]) -> Result<i128, ProgramError> {
+        take(input).map(i128::from_le_bytes)
+    }
+
+    pub fn read_u128(input: &mut &[u8]) -> Result<u128, ProgramError> {
+        take(input).map(u128::from_le_bytes)
+    }
+
+    pub fn read_pubkey(input: &mut &[u8]) -> Result<Pubkey, ProgramError> {
+        take(input).map(Pubkey::new_from_array)
+    }
+
+    pub fn read_bytes32(input: &mut &[u8]) -> Result<[u8; 32], ProgramError> {
+        take(input)
+    }
+}
+
 // 4. mod ix
 pub mod ix {
     use solana_program::{pubkey::Pubkey, program_error::ProgramError};
     use percolator::RiskParams;
+    use crate::error::PercolatorError;
+    use crate::le::{read_u8, read_u16, read_u32, read_u64, read_i64, read_i128, read_u128, read_pubkey, read_bytes32};
 
     #[derive(Debug)]
     pub enum Instruction {
@@ -1069,13 +1209,17 @@ pub mod ix {
             thresh_max: u128,
             thresh_min_step: u128,
         },
+        /// Toggle reduce-only trading for the market. Admin only.
+        SetReduceOnly { enabled: u8 },
+        /// Update oracle staleness and confidence limits. Admin only.
+        SetOracleParams { max_staleness_secs: u64, conf_filter_bps: u16 },
     }
 
     impl Instruction {
         pub fn decode(input: &[u8]) -> Result<Self, ProgramError> {
             let (&tag, mut rest) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
-            
-            match tag {
+
+            let ix = match tag {
                 0 => { // InitMarket
                     let admin = read_pubkey(&mut rest)?;
                     let collateral_mint = read_pubkey(&mut rest)?;
@@ -1171,73 +1315,26 @@ pub mod ix {
                         thresh_step_bps, thresh_alpha_bps, thresh_min, thresh_max, thresh_min_step,
                     })
                 },
+                15 => { // SetReduceOnly
+                    let enabled = read_u8(&mut rest)?;
+                    Ok(Instruction::SetReduceOnly { enabled })
+                },
+                16 => { // SetOracleParams
+                    let max_staleness_secs = read_u64(&mut rest)?;
+                    let conf_filter_bps = read_u16(&mut rest)?;
+                    Ok(Instruction::SetOracleParams { max_staleness_secs, conf_filter_bps })
+                },
                 _ => Err(ProgramError::InvalidInstructionData),
+            }?;
+
+            // Every variant has a fixed-size payload: reject anything left over
+            if !rest.is_empty() {
+                return Err(PercolatorError::TrailingBytes.into());
             }
+            Ok(ix)
         }
     }
 
-    fn read_u8(input: &mut &[u8]) -> Result<u8, ProgramError> {
-        let (&val, rest) = input.split_first().ok_or(ProgramError::InvalidInstructionData)?;
-        *input = rest;
-        Ok(val)
-    }
-
-    fn read_u16(input: &mut &[u8]) -> Result<u16, ProgramError> {
-        if input.len() < 2 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(2);
-        *input = rest;
-        Ok(u16::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_u32(input: &mut &[u8]) -> Result<u32, ProgramError> {
-        if input.len() < 4 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(4);
-        *input = rest;
-        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_u64(input: &mut &[u8]) -> Result<u64, ProgramError> {
-        if input.len() < 8 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(8);
-        *input = rest;
-        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_i64(input: &mut &[u8]) -> Result<i64, ProgramError> {
-        if input.len() < 8 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(8);
-        *input = rest;
-        Ok(i64::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_i128(input: &mut &[u8]) -> Result<i128, ProgramError> {
-        if input.len() < 16 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(16);
-        *input = rest;
-        Ok(i128::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_u128(input: &mut &[u8]) -> Result<u128, ProgramError> {
-        if input.len() < 16 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(16);
-        *input = rest;
-        Ok(u128::from_le_bytes(bytes.try_into().unwrap()))
-    }
-
-    fn read_pubkey(input: &mut &[u8]) -> Result<Pubkey, ProgramError> {
-        if input.len() < 32 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(32);
-        *input = rest;
-        Ok(Pubkey::new_from_array(bytes.try_into().unwrap()))
-    }
-
-    fn read_bytes32(input: &mut &[u8]) -> Result<[u8; 32], ProgramError> {
-        if input.len() < 32 { return Err(ProgramError::InvalidInstructionData); }
-        let (bytes, rest) = input.split_at(32);
-        *input = rest;
-        Ok(bytes.try_into().unwrap())
-    }
-
     fn read_risk_params(input: &mut &[u8]) -> Result<RiskParams, ProgramError> {
         Ok(RiskParams {
             warmup_period_slots: read_u64(input)?,
@@ -1265,7 +1362,7 @@ pub mod accounts {
     pub fn expect_len(accounts: &[AccountInfo], n: usize) -> Result<(), ProgramError> {
         // Length check via verify helper (Kani-provable)
         if !crate::verify::len_ok(accounts.len(), n) {
-            return Err(ProgramError::NotEnoughAccountKeys);
+            return Err(PercolatorError::MissingAccount.into());
         }
         Ok(())
     }
@@ -1314,6 +1411,7 @@ pub mod state {
     use solana_program::account_info::AccountInfo;
     use solana_program::program_error::ProgramError;
     use crate::constants::{HEADER_LEN, CONFIG_LEN};
+    use crate::le;
 
     #[repr(C)]
     #[derive(Clone, Copy, Pod, Zeroable)]
@@ -1321,7 +1419,9 @@ pub mod state {
         pub magic: u64,
         pub version: u32,
         pub bump: u8,
-        pub _padding: [u8; 3],
+        /// Market flags (FLAG_REDUCE_ONLY)
+        pub flags: u8,
+        pub _padding: [u8; 2],
         pub admin: [u8; 32],
         pub _reserved: [u8; 24], // [0..8]=nonce, [8..16]=last_thr_slot, [16..24]=dust_base
     }
@@ -1402,10 +1502,15 @@ pub mod state {
         dst.copy_from_slice(src);
     }
 
+    /// Read the little-endian u64 at `off`, or TruncatedData if `data` is too short.
+    fn read_reserved_u64(data: &[u8], off: usize) -> Result<u64, ProgramError> {
+        le::read_u64(&mut data.get(off..).unwrap_or_default())
+    }
+
     /// Read the request nonce from the reserved field in slab header.
     /// The nonce is stored at RESERVED_OFF..RESERVED_OFF+8 as little-endian u64.
-    pub fn read_req_nonce(data: &[u8]) -> u64 {
-        u64::from_le_bytes(data[RESERVED_OFF..RESERVED_OFF + 8].try_into().unwrap())
+    pub fn read_req_nonce(data: &[u8]) -> Result<u64, ProgramError> {
+        read_reserved_u64(data, RESERVED_OFF)
     }
 
     /// Write the request nonce to the reserved field in slab header.
@@ -1418,8 +1523,8 @@ pub mod state {
     }
 
     /// Read the last threshold update slot from _reserved[8..16].
-    pub fn read_last_thr_update_slot(data: &[u8]) -> u64 {
-        u64::from_le_bytes(data[RESERVED_OFF + 8..RESERVED_OFF + 16].try_into().unwrap())
+    pub fn read_last_thr_update_slot(data: &[u8]) -> Result<u64, ProgramError> {
+        read_reserved_u64(data, RESERVED_OFF + 8)
     }
 
     /// Write the last threshold update slot to _reserved[8..16].
@@ -1428,8 +1533,8 @@ pub mod state {
     }
 
     /// Read accumulated dust (base token remainder) from _reserved[16..24].
-    pub fn read_dust_base(data: &[u8]) -> u64 {
-        u64::from_le_bytes(data[RESERVED_OFF + 16..RESERVED_OFF + 24].try_into().unwrap())
+    pub fn read_dust_base(data: &[u8]) -> Result<u64, ProgramError> {
+        read_reserved_u64(data, RESERVED_OFF + 16)
     }
 
     /// Write accumulated dust (base token remainder) to _reserved[16..24].
@@ -1491,6 +1596,7 @@ pub mod units {
 pub mod oracle {
     use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
     use crate::error::PercolatorError;
+    use crate::le;
 
     // SECURITY (H5): The "devnet" feature disables critical oracle safety checks:
     // - Staleness validation (stale prices accepted)
@@ -1571,16 +1677,16 @@ pub mod oracle {
         }
 
         // Validate feed_id matches expected
-        let feed_id: [u8; 32] = data[OFF_FEED_ID..OFF_FEED_ID + 32].try_into().unwrap();
+        let feed_id = le::read_bytes32(&mut &data[OFF_FEED_ID..])?;
         if &feed_id != expected_feed_id {
             return Err(PercolatorError::InvalidOracleKey.into());
         }
 
         // Read price fields
-        let price = i64::from_le_bytes(data[OFF_PRICE..OFF_PRICE + 8].try_into().unwrap());
-        let conf = u64::from_le_bytes(data[OFF_CONF..OFF_CONF + 8].try_into().unwrap());
-        let expo = i32::from_le_bytes(data[OFF_EXPO..OFF_EXPO + 4].try_into().unwrap());
-        let publish_time = i64::from_le_bytes(data[OFF_PUBLISH_TIME..OFF_PUBLISH_TIME + 8].try_into().unwrap());
+        let price = le::read_i64(&mut &data[OFF_PRICE..])?;
+        let conf = le::read_u64(&mut &data[OFF_CONF..])?;
+        let expo = le::read_i32(&mut &data[OFF_EXPO..])?;
+        let publish_time = le::read_i64(&mut &data[OFF_PUBLISH_TIME..])?;
 
         if price <= 0 {
             return Err(PercolatorError::OracleInvalid.into());
@@ -1673,13 +1779,9 @@ pub mod oracle {
         let decimals = data[CL_OFF_DECIMALS];
 
         // Read price data directly from fixed offsets
-        let timestamp = u64::from_le_bytes(
-            data[CL_OFF_TIMESTAMP..CL_OFF_TIMESTAMP + 8].try_into().unwrap()
-        );
+        let timestamp = le::read_u64(&mut &data[CL_OFF_TIMESTAMP..])?;
         // Read answer as i128 (16 bytes), but only bottom 8 bytes are typically used
-        let answer = i128::from_le_bytes(
-            data[CL_OFF_ANSWER..CL_OFF_ANSWER + 16].try_into().unwrap()
-        );
+        let answer = le::read_i128(&mut &data[CL_OFF_ANSWER..])?;
 
         if answer <= 0 {
             return Err(PercolatorError::OracleInvalid.into());
@@ -1812,19 +1914,25 @@ pub mod collateral {
         }
         #[cfg(feature = "test")]
         {
-            let mut src_data = source.try_borrow_mut_data()?;
-            let mut src_state = TokenAccount::unpack(&src_data)?;
-            src_state.amount = src_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
-            TokenAccount::pack(src_state, &mut src_data)?;
-
-            let mut dst_data = dest.try_borrow_mut_data()?;
-            let mut dst_state = TokenAccount::unpack(&dst_data)?;
-            dst_state.amount = dst_state.amount.checked_add(amount).ok_or(ProgramError::InvalidAccountData)?;
-            TokenAccount::pack(dst_state, &mut dst_data)?;
-            Ok(())
+            mock_transfer(source, dest, amount)
         }
     }
 
+    /// Test stand-in for the token program's transfer. Like the real CPI it
+    /// writes nothing unless both sides can be updated.
+    #[cfg(feature = "test")]
+    fn mock_transfer(source: &AccountInfo, dest: &AccountInfo, amount: u64) -> Result<(), ProgramError> {
+        let mut src_data = source.try_borrow_mut_data()?;
+        let mut dst_data = dest.try_borrow_mut_data()?;
+        let mut src_state = TokenAccount::unpack(&src_data)?;
+        let mut dst_state = TokenAccount::unpack(&dst_data)?;
+        src_state.amount = src_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
+        dst_state.amount = dst_state.amount.checked_add(amount).ok_or(ProgramError::InvalidAccountData)?;
+        TokenAccount::pack(src_state, &mut src_data)?;
+        TokenAccount::pack(dst_state, &mut dst_data)?;
+        Ok(())
+    }
+
     pub fn withdraw<'a>(
         _token_program: &AccountInfo<'a>,
         source: &AccountInfo<'a>,
@@ -1848,16 +1956,7 @@ pub mod collateral {
         }
         #[cfg(feature = "test")]
         {
-            let mut src_data = source.try_borrow_mut_data()?;
-            let mut src_state = TokenAccount::unpack(&src_data)?;
-            src_state.amount = src_state.amount.checked_sub(amount).ok_or(ProgramError::InsufficientFunds)?;
-            TokenAccount::pack(src_state, &mut src_data)?;
-
-            let mut dst_data = dest.try_borrow_mut_data()?;
-            let mut dst_state = TokenAccount::unpack(&dst_data)?;
-            dst_state.amount = dst_state.amount.checked_add(amount).ok_or(ProgramError::InvalidAccountData)?;
-            TokenAccount::pack(dst_state, &mut dst_data)?;
-            Ok(())
+            mock_transfer(source, dest, amount)
         }
     }
 }
@@ -1869,6 +1968,7 @@ pub mod processor {
         sysvar::{clock::Clock, Sysvar},
         program_error::ProgramError,
         program_pack::Pack,
+        program::set_return_data,
         msg,
         log::{sol_log_compute_units, sol_log_64},
     };
@@ -1876,7 +1976,7 @@ pub mod processor {
         ix::Instruction,
         state::{self, SlabHeader, MarketConfig},
         accounts,
-        constants::{MAGIC, VERSION, SLAB_LEN, CONFIG_LEN, MATCHER_CONTEXT_LEN, MATCHER_CALL_TAG, MATCHER_CALL_LEN, MATCHER_CONTEXT_PREFIX_LEN,
+        constants::{MAGIC, VERSION, SLAB_LEN, FLAG_REDUCE_ONLY, CONFIG_LEN, MATCHER_CONTEXT_LEN, MATCHER_CALL_TAG, MATCHER_CALL_LEN, MATCHER_CONTEXT_PREFIX_LEN,
             DEFAULT_FUNDING_HORIZON_SLOTS, DEFAULT_FUNDING_K_BPS, DEFAULT_FUNDING_INV_SCALE_NOTIONAL_E6, DEFAULT_FUNDING_MAX_PREMIUM_BPS, DEFAULT_FUNDING_MAX_BPS_PER_SLOT,
             DEFAULT_THRESH_FLOOR, DEFAULT_THRESH_RISK_BPS, DEFAULT_THRESH_UPDATE_INTERVAL_SLOTS, DEFAULT_THRESH_STEP_BPS, DEFAULT_THRESH_ALPHA_BPS, DEFAULT_THRESH_MIN, DEFAULT_THRESH_MAX, DEFAULT_THRESH_MIN_STEP},
         error::{PercolatorError, map_risk_error},
@@ -1887,6 +1987,26 @@ pub mod processor {
     use percolator::{RiskEngine, NoOpMatcher, MAX_ACCOUNTS, MatchingEngine, TradeExecution, RiskError};
     use solana_program::instruction::{Instruction as SolInstruction, AccountMeta};
 
+    /// Publish the fill outcome as return data (see `trade_result`).
+    /// `fee_revenue_before` is read just before execute_trade; the fee is
+    /// whatever the engine booked, not a re-derivation of its formula.
+    fn publish_trade_result(engine: &RiskEngine, lp_idx: u16, user_idx: u16, exec_price_e6: u64, exec_size: i128, oracle_price_e6: u64, fee_revenue_before: u128) {
+        let user = &engine.accounts[user_idx as usize];
+        let result = crate::trade_result::TradeResult {
+            version: crate::constants::TRADE_RESULT_VERSION,
+            lp_idx,
+            user_idx,
+            exec_price_e6,
+            exec_size,
+            fee: engine.insurance_fund.fee_revenue.saturating_sub(fee_revenue_before),
+            user_position: user.position_size,
+            user_entry_price_e6: user.entry_price,
+            oracle_price_e6,
+            user_capital: user.capital,
+        };
+        set_return_data(&result.encode());
+    }
+
     struct CpiMatcher {
         exec_price: u64,
         exec_size: i128,
@@ -2079,11 +2199,35 @@ pub mod processor {
                 let _ = zc::engine_mut(&mut data)?;
 
                 let header = state::read_header(&data);
-                if header.magic == MAGIC { return Err(PercolatorError::AlreadyInitialized.into()); }
+                if header.magic == MAGIC {
+                    // Re-running InitMarket with the parameters the market was created
+                    // with is a no-op, so deploy scripts can call it unconditionally.
+                    // The risk threshold is crank-managed after init and not compared.
+                    let config = state::read_config(&data);
+                    let engine = zc::engine_ref(&data)?;
+                    let mut requested = risk_params;
+                    requested.risk_reduction_threshold = engine.params.risk_reduction_threshold;
+                    let same = header.admin == a_admin.key.to_bytes()
+                        && config.collateral_mint == a_mint.key.to_bytes()
+                        && config.vault_pubkey == a_vault.key.to_bytes()
+                        && config.index_feed_id == index_feed_id
+                        && config.max_staleness_secs == max_staleness_secs
+                        && config.conf_filter_bps == conf_filter_bps
+                        && config.invert == invert
+                        && config.unit_scale == unit_scale
+                        && engine.params == requested;
+                    if !same { return Err(PercolatorError::AlreadyInitialized.into()); }
+                    msg!("INIT_MARKET_NOOP");
+                    return Ok(());
+                }
 
                 let (auth, bump) = accounts::derive_vault_authority(program_id, a_slab.key);
                 verify_vault(a_vault, &auth, a_mint.key, a_vault.key)?;
 
+                // Read the clock before zeroing so a bad clock account leaves the slab untouched
+                let a_clock = &accounts[5];
+                let clock = Clock::from_account_info(a_clock)?;
+
                 for b in data.iter_mut() { *b = 0; }
 
                 // Initialize engine in-place (zero-copy) to avoid stack overflow.
@@ -2093,8 +2237,6 @@ pub mod processor {
 
                 // Initialize slot fields to current slot to prevent overflow on first crank
                 // (accrue_funding checks dt < 31_536_000, which fails if last_funding_slot=0)
-                let a_clock = &accounts[5];
-                let clock = Clock::from_account_info(a_clock)?;
                 engine.current_slot = clock.slot;
                 engine.last_funding_slot = clock.slot;
                 engine.last_crank_slot = clock.slot;
@@ -2130,7 +2272,8 @@ pub mod processor {
                     magic: MAGIC,
                     version: VERSION,
                     bump,
-                    _padding: [0; 3],
+                    flags: 0,
+                    _padding: [0; 2],
                     admin: a_admin.key.to_bytes(),
                     _reserved: [0; 24],
                 };
@@ -2162,19 +2305,19 @@ pub mod processor {
                 verify_vault(a_vault, &auth, &mint, &Pubkey::new_from_array(config.vault_pubkey))?;
                 verify_token_account(a_user_ata, a_user.key, &mint)?;
 
-                // Transfer base tokens to vault
-                collateral::deposit(a_token, a_user_ata, a_vault, a_user, fee_payment)?;
-
                 // Convert base tokens to units for engine
                 let (units, dust) = crate::units::base_to_units(fee_payment, config.unit_scale);
 
-                // Accumulate dust
-                let old_dust = state::read_dust_base(&data);
-                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
-
                 let engine = zc::engine_mut(&mut data)?;
                 let idx = engine.add_user(units as u128).map_err(map_risk_error)?;
                 engine.set_owner(idx, a_user.key.to_bytes()).map_err(map_risk_error)?;
+
+                // Transfer base tokens to vault
+                collateral::deposit(a_token, a_user_ata, a_vault, a_user, fee_payment)?;
+
+                // Accumulate dust last: nothing after this can fail
+                let old_dust = state::read_dust_base(&data)?;
+                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
             },
             Instruction::InitLP { matcher_program, matcher_context, fee_payment } => {
                 accounts::expect_len(accounts, 5)?;
@@ -2198,19 +2341,19 @@ pub mod processor {
                 verify_vault(a_vault, &auth, &mint, &Pubkey::new_from_array(config.vault_pubkey))?;
                 verify_token_account(a_user_ata, a_user.key, &mint)?;
 
-                // Transfer base tokens to vault
-                collateral::deposit(a_token, a_user_ata, a_vault, a_user, fee_payment)?;
-
                 // Convert base tokens to units for engine
                 let (units, dust) = crate::units::base_to_units(fee_payment, config.unit_scale);
 
-                // Accumulate dust
-                let old_dust = state::read_dust_base(&data);
-                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
-
                 let engine = zc::engine_mut(&mut data)?;
                 let idx = engine.add_lp(matcher_program.to_bytes(), matcher_context.to_bytes(), units as u128).map_err(map_risk_error)?;
                 engine.set_owner(idx, a_user.key.to_bytes()).map_err(map_risk_error)?;
+
+                // Transfer base tokens to vault
+                collateral::deposit(a_token, a_user_ata, a_vault, a_user, fee_payment)?;
+
+                // Accumulate dust last: nothing after this can fail
+                let old_dust = state::read_dust_base(&data)?;
+                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
             },
             Instruction::DepositCollateral { user_idx, amount } => {
                 accounts::expect_len(accounts, 5)?;
@@ -2234,16 +2377,9 @@ pub mod processor {
                 verify_vault(a_vault, &auth, &mint, &Pubkey::new_from_array(config.vault_pubkey))?;
                 verify_token_account(a_user_ata, a_user.key, &mint)?;
 
-                // Transfer base tokens to vault
-                collateral::deposit(a_token, a_user_ata, a_vault, a_user, amount)?;
-
                 // Convert base tokens to units for engine
                 let (units, dust) = crate::units::base_to_units(amount, config.unit_scale);
 
-                // Accumulate dust
-                let old_dust = state::read_dust_base(&data);
-                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
-
                 let engine = zc::engine_mut(&mut data)?;
 
                 check_idx(engine, user_idx)?;
@@ -2255,6 +2391,13 @@ pub mod processor {
                 }
 
                 engine.deposit(user_idx, units as u128).map_err(map_risk_error)?;
+
+                // Transfer base tokens to vault
+                collateral::deposit(a_token, a_user_ata, a_vault, a_user, amount)?;
+
+                // Accumulate dust last: nothing after this can fail
+                let old_dust = state::read_dust_base(&data)?;
+                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
             },
             Instruction::WithdrawCollateral { user_idx, amount } => {
                 accounts::expect_len(accounts, 8)?;
@@ -2360,7 +2503,7 @@ pub mod processor {
                 let config = state::read_config(&data);
                 let header = state::read_header(&data);
                 // Read last threshold update slot BEFORE mutable engine borrow
-                let last_thr_slot = state::read_last_thr_update_slot(&data);
+                let last_thr_slot = state::read_last_thr_update_slot(&data)?;
 
                 // SECURITY (C4): allow_panic triggers global settlement - admin only
                 // This prevents griefing attacks where anyone triggers panic at worst moment
@@ -2372,7 +2515,7 @@ pub mod processor {
                 }
 
                 // Read dust before borrowing engine (for dust sweep later)
-                let dust_before = state::read_dust_base(&data);
+                let dust_before = state::read_dust_base(&data)?;
                 let unit_scale = config.unit_scale;
 
                 let engine = zc::engine_mut(&mut data)?;
@@ -2424,7 +2567,7 @@ pub mod processor {
                     msg!("CU_CHECKPOINT: keeper_crank_start");
                     sol_log_compute_units();
                 }
-                let _outcome = engine.keeper_crank(effective_caller_idx, clock.slot, price, effective_funding_rate, allow_panic != 0).map_err(map_risk_error)?;
+                let outcome = engine.keeper_crank(effective_caller_idx, clock.slot, price, effective_funding_rate, allow_panic != 0).map_err(map_risk_error)?;
                 #[cfg(feature = "cu-audit")]
                 {
                     msg!("CU_CHECKPOINT: keeper_crank_end");
@@ -2497,6 +2640,20 @@ pub mod processor {
                 // Debug: log lifetime counters (sol_log_64: tag, liqs, force, max_accounts, insurance)
                 msg!("CRANK_STATS");
                 sol_log_64(0xC8A4C, liqs, force, MAX_ACCOUNTS as u64, ins_low);
+
+                // Sweep summary on each completed full sweep: totals since the previous
+                // completion. sol_log_64 takes u64 words, so saturate rather than wrap
+                // (an out-of-range total reads as u64::MAX, or i64::MIN/MAX bits)
+                if let Some(sweep) = outcome.sweep_summary {
+                    msg!("SWEEP_SUMMARY");
+                    sol_log_64(
+                        0x5EE9,
+                        sweep.fee_revenue.min(u64::MAX as u128) as u64,
+                        sweep.funding_index_delta.clamp(i64::MIN as i128, i64::MAX as i128) as i64 as u64,
+                        sweep.volume.min(u64::MAX as u128) as u64,
+                        sweep.liquidations,
+                    );
+                }
             },
             Instruction::TradeNoCpi { lp_idx, user_idx, size } => {
                 accounts::expect_len(accounts, 5)?;
@@ -2512,6 +2669,7 @@ pub mod processor {
                 slab_guard(program_id, a_slab, &data)?;
                 require_initialized(&data)?;
                 let config = state::read_config(&data);
+                let reduce_only = state::read_header(&data).flags & FLAG_REDUCE_ONLY != 0;
 
                 let engine = zc::engine_mut(&mut data)?;
 
@@ -2529,6 +2687,10 @@ pub mod processor {
                     return Err(PercolatorError::EngineUnauthorized.into());
                 }
 
+                if reduce_only && !crate::verify::reduce_only_ok(engine.accounts[user_idx as usize].position_size, size) {
+                    return Err(PercolatorError::MarketReduceOnly.into());
+                }
+
                 let clock = Clock::from_account_info(&accounts[3])?;
                 let a_oracle = &accounts[4];
 
@@ -2573,12 +2735,14 @@ pub mod processor {
                     msg!("CU_CHECKPOINT: trade_nocpi_execute_start");
                     sol_log_compute_units();
                 }
+                let fee_revenue_before = engine.insurance_fund.fee_revenue;
                 engine.execute_trade(&NoOpMatcher, lp_idx, user_idx, clock.slot, price, size).map_err(map_risk_error)?;
                 #[cfg(feature = "cu-audit")]
                 {
                     msg!("CU_CHECKPOINT: trade_nocpi_execute_end");
                     sol_log_compute_units();
                 }
+                publish_trade_result(engine, lp_idx, user_idx, price, size, price, fee_revenue_before);
             },
             Instruction::TradeCpi { lp_idx, user_idx, size } => {
                 // Phase 1: Updated account layout - lp_pda must be in accounts
@@ -2639,7 +2803,7 @@ pub mod processor {
 
                     // Phase 3: Monotonic nonce for req_id (prevents replay attacks)
                     // Nonce advancement via verify helper (Kani-provable)
-                    let nonce = state::read_req_nonce(&*data);
+                    let nonce = state::read_req_nonce(&*data)?;
                     let req_id = crate::verify::nonce_on_success(nonce);
 
                     let engine = zc::engine_ref(&*data)?;
@@ -2741,8 +2905,14 @@ pub mod processor {
                 let matcher = CpiMatcher { exec_price: ret.exec_price_e6, exec_size: ret.exec_size };
                 {
                     let mut data = state::slab_data_mut(a_slab)?;
+                    let reduce_only = state::read_header(&data).flags & FLAG_REDUCE_ONLY != 0;
                     let engine = zc::engine_mut(&mut data)?;
 
+                    // Reduce-only applies to what the matcher actually filled
+                    if reduce_only && !crate::verify::reduce_only_ok(engine.accounts[user_idx as usize].position_size, ret.exec_size) {
+                        return Err(PercolatorError::MarketReduceOnly.into());
+                    }
+
                     // Gate: if insurance_fund <= threshold, only allow risk-reducing trades
                     // Use actual exec_size from matcher (LP delta is -exec_size)
                     // O(1) check after single O(n) scan
@@ -2774,12 +2944,14 @@ pub mod processor {
                         msg!("CU_CHECKPOINT: trade_cpi_execute_start");
                         sol_log_compute_units();
                     }
+                    let fee_revenue_before = engine.insurance_fund.fee_revenue;
                     engine.execute_trade(&matcher, lp_idx, user_idx, clock.slot, price, trade_size).map_err(map_risk_error)?;
                     #[cfg(feature = "cu-audit")]
                     {
                         msg!("CU_CHECKPOINT: trade_cpi_execute_end");
                         sol_log_compute_units();
                     }
+                    publish_trade_result(engine, lp_idx, user_idx, ret.exec_price_e6, trade_size, price, fee_revenue_before);
                     // Write nonce AFTER CPI and execute_trade to avoid ExternalAccountDataModified
                     state::write_req_nonce(&mut data, req_id);
                 }
@@ -2917,18 +3089,18 @@ pub mod processor {
                 verify_vault(a_vault, &auth, &mint, &Pubkey::new_from_array(config.vault_pubkey))?;
                 verify_token_account(a_user_ata, a_user.key, &mint)?;
 
-                // Transfer base tokens to vault
-                collateral::deposit(a_token, a_user_ata, a_vault, a_user, amount)?;
-
                 // Convert base tokens to units for engine
                 let (units, dust) = crate::units::base_to_units(amount, config.unit_scale);
 
-                // Accumulate dust
-                let old_dust = state::read_dust_base(&data);
-                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
-
                 let engine = zc::engine_mut(&mut data)?;
                 engine.top_up_insurance_fund(units as u128).map_err(map_risk_error)?;
+
+                // Transfer base tokens to vault
+                collateral::deposit(a_token, a_user_ata, a_vault, a_user, amount)?;
+
+                // Accumulate dust last: nothing after this can fail
+                let old_dust = state::read_dust_base(&data)?;
+                state::write_dust_base(&mut data, old_dust.saturating_add(dust));
             },
             Instruction::SetRiskThreshold { new_threshold } => {
                 accounts::expect_len(accounts, 2)?;
@@ -2999,7 +3171,7 @@ pub mod processor {
                     }
 
                     // Bug #3 fix: Check dust_base to prevent closing with unaccounted funds
-                    let dust_base = state::read_dust_base(&data);
+                    let dust_base = state::read_dust_base(&data)?;
                     if dust_base != 0 {
                         return Err(PercolatorError::EngineInsufficientBalance.into());
                     }
@@ -3070,6 +3242,55 @@ pub mod processor {
                 config.thresh_min_step = thresh_min_step;
                 state::write_config(&mut data, &config);
             }
+
+            Instruction::SetReduceOnly { enabled } => {
+                accounts::expect_len(accounts, 2)?;
+                let a_admin = &accounts[0];
+                let a_slab = &accounts[1];
+
+                accounts::expect_signer(a_admin)?;
+                accounts::expect_writable(a_slab)?;
+
+                let mut data = state::slab_data_mut(a_slab)?;
+                slab_guard(program_id, a_slab, &data)?;
+                require_initialized(&data)?;
+
+                let mut header = state::read_header(&data);
+                require_admin(header.admin, a_admin.key)?;
+
+                if enabled != 0 {
+                    header.flags |= FLAG_REDUCE_ONLY;
+                } else {
+                    header.flags &= !FLAG_REDUCE_ONLY;
+                }
+                state::write_header(&mut data, &header);
+            }
+
+            Instruction::SetOracleParams { max_staleness_secs, conf_filter_bps } => {
+                accounts::expect_len(accounts, 2)?;
+                let a_admin = &accounts[0];
+                let a_slab = &accounts[1];
+
+                accounts::expect_signer(a_admin)?;
+                accounts::expect_writable(a_slab)?;
+
+                let mut data = state::slab_data_mut(a_slab)?;
+                slab_guard(program_id, a_slab, &data)?;
+                require_initialized(&data)?;
+
+                let header = state::read_header(&data);
+                require_admin(header.admin, a_admin.key)?;
+
+                // Zero staleness would reject every price; conf above 100% disables the filter
+                if max_staleness_secs == 0 || conf_filter_bps > 10_000 {
+                    return Err(PercolatorError::InvalidConfigParam.into());
+                }
+
+                let mut config = state::read_config(&data);
+                config.max_staleness_secs = max_staleness_secs;
+                config.conf_filter_bps = conf_filter_bps;
+                state::write_config(&mut data, &config);
+            }
         }
         Ok(())
     }
@@ -29,10 +29,10 @@ use std::path::PathBuf;
 
 // SLAB_LEN for SBF - differs between test and production
 #[cfg(feature = "test")]
-const SLAB_LEN: usize = 18144;  // MAX_ACCOUNTS=64 (0x46e0)
+const SLAB_LEN: usize = 18216;  // MAX_ACCOUNTS=64 (0x4728)
 
 #[cfg(not(feature = "test"))]
-const SLAB_LEN: usize = 1111384;  // MAX_ACCOUNTS=4096 (0x10f558)
+const SLAB_LEN: usize = 1111456;  // MAX_ACCOUNTS=4096 (0x10f5a0)
 
 #[cfg(feature = "test")]
 const MAX_ACCOUNTS: usize = 64;
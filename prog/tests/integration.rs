@@ -28,7 +28,7 @@ use std::path::PathBuf;
 // Note: We use production BPF (not test feature) because test feature
 // bypasses CPI for token transfers, which fails in LiteSVM.
 // Bug #7 fix increased pending_exclude_epoch from [u8; 4096] to [u16; 4096]
-const SLAB_LEN: usize = 1111384;  // MAX_ACCOUNTS=4096 (0x10f558)
+const SLAB_LEN: usize = 1111456;  // MAX_ACCOUNTS=4096 (0x10f5a0)
 const MAX_ACCOUNTS: usize = 4096;
 
 // Pyth Receiver program ID
@@ -27,6 +27,7 @@ use percolator_prog::constants::MATCHER_ABI_VERSION;
 use percolator_prog::verify::{
     owner_ok, admin_ok, matcher_identity_ok, matcher_shape_ok, MatcherAccountsShape,
     gate_active, nonce_on_success, nonce_on_failure, pda_key_matches, cpi_trade_size,
+    reduce_only_ok,
     // Account validation helpers
     signer_ok, writable_ok, len_ok,
     LpPdaShape, lp_pda_shape_ok, oracle_feed_id_ok,
@@ -2932,3 +2933,27 @@ fn kani_scale_price_e6_concrete_example() {
 // boundaries, but this is unavoidable with integer arithmetic and economically
 // insignificant compared to the original bug (factor of unit_scale difference).
 
+// =============================================================================
+// AM. REDUCE-ONLY MODE (2 proofs)
+// =============================================================================
+
+/// Prove: an accepted reduce-only trade never grows |position| or flips its side
+#[kani::proof]
+fn kani_reduce_only_never_grows_or_flips() {
+    let old_pos: i128 = kani::any();
+    let delta: i128 = kani::any();
+    kani::assume(old_pos > i128::MIN && delta > i128::MIN);
+    kani::assume(reduce_only_ok(old_pos, delta));
+
+    let new_pos = old_pos + delta;
+    assert!(new_pos.unsigned_abs() <= old_pos.unsigned_abs(), "position must not grow");
+    assert!(new_pos == 0 || new_pos.signum() == old_pos.signum(), "position must not flip");
+}
+
+/// Prove: a flat account cannot open under reduce-only
+#[kani::proof]
+fn kani_reduce_only_rejects_open_from_flat() {
+    let delta: i128 = kani::any();
+    kani::assume(delta != 0);
+    assert!(!reduce_only_ok(0, delta), "flat account must not open a position");
+}
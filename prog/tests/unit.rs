@@ -276,6 +276,17 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         vec![13u8]
     }
 
+    fn encode_set_reduce_only(enabled: u8) -> Vec<u8> {
+        vec![15u8, enabled]
+    }
+
+    fn encode_set_oracle_params(max_staleness_secs: u64, conf_filter_bps: u16) -> Vec<u8> {
+        let mut data = vec![16u8];
+        encode_u64(max_staleness_secs, &mut data);
+        encode_u16(conf_filter_bps, &mut data);
+        data
+    }
+
     fn encode_topup_insurance(amount: u64) -> Vec<u8> {
         let mut data = vec![9u8];
         encode_u64(amount, &mut data);
@@ -441,6 +452,50 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         assert_eq!(res, Err(PercolatorError::InvalidVaultAta.into()));
     }
 
+    #[test]
+    fn test_init_market_bad_clock_leaves_slab_untouched() {
+        let mut f = setup_market();
+        f.slab.data.iter_mut().for_each(|b| *b = 0x11);
+        f.clock.data = vec![];
+        let before = f.slab.data.clone();
+        let init_data = encode_init_market(&f, 100);
+        let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+        let init_accounts = vec![
+            f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(),
+            f.token_prog.to_info(), f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+        ];
+        assert!(process_instruction(&f.program_id, &init_accounts, &init_data).is_err());
+        drop(init_accounts);
+        assert!(f.slab.data == before, "failed InitMarket must not zero the slab");
+    }
+
+    #[test]
+    fn test_deposit_unknown_idx_moves_no_tokens() {
+        let mut f = setup_market();
+        let init_data = encode_init_market(&f, 100);
+        {
+            let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+            let init_accounts = vec![
+                f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(),
+                f.token_prog.to_info(), f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+            ];
+            process_instruction(&f.program_id, &init_accounts, &init_data).unwrap();
+        }
+
+        let mut user = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let mut user_ata = TestAccount::new(Pubkey::new_unique(), spl_token::ID, 0, make_token_account(f.mint.key, user.key, 1000)).writable();
+        {
+            let accounts = vec![
+                user.to_info(), f.slab.to_info(), user_ata.to_info(), f.vault.to_info(), f.token_prog.to_info(),
+            ];
+            let res = process_instruction(&f.program_id, &accounts, &encode_deposit(7, 500));
+            assert!(res.is_err());
+        }
+
+        assert_eq!(TokenAccount::unpack(&user_ata.data).unwrap().amount, 1000);
+        assert_eq!(TokenAccount::unpack(&f.vault.data).unwrap().amount, 0);
+    }
+
     #[test]
     fn test_trade() {
         let mut f = setup_market();
@@ -979,7 +1034,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         }
 
         // Verify threshold update ran by checking last_thr_update_slot
-        let last_thr_slot_after = state::read_last_thr_update_slot(&f.slab.data);
+        let last_thr_slot_after = state::read_last_thr_update_slot(&f.slab.data).unwrap();
         assert_eq!(last_thr_slot_after, 100, "last_thr_update_slot should be set to clock.slot after crank");
 
         // Check if positions are still non-zero after crank
@@ -1490,6 +1545,22 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         assert_eq!(price_inv_scaled, 10, "Inverted+scaled price should be 10 e6");
     }
 
+    #[test]
+    fn test_chainlink_short_answer_is_truncated_data() {
+        // A 224-byte account passes the length gate but the i128 answer at 216 needs 232 bytes
+        use percolator_prog::oracle::read_chainlink_price_e6;
+
+        let key = Pubkey::new_unique();
+        let mut data = vec![0u8; 224];
+        data[138] = 6;
+        data[208..216].copy_from_slice(&100u64.to_le_bytes());
+        data[216..224].copy_from_slice(&100_000_000u64.to_le_bytes());
+        let mut oracle = TestAccount::new(key, Pubkey::new_unique(), 0, data);
+
+        let res = read_chainlink_price_e6(&oracle.to_info(), &key.to_bytes(), 100, 100);
+        assert_eq!(res, Err(PercolatorError::TruncatedData.into()));
+    }
+
     #[test]
     fn test_unit_scale_conversion() {
         // Test base_to_units and units_to_base with unit_scale
@@ -1659,7 +1730,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         let user_idx = find_idx_by_owner(&f.slab.data, user.key).unwrap();
 
         // Record initial state
-        let dust_start = state::read_dust_base(&f.slab.data);
+        let dust_start = state::read_dust_base(&f.slab.data).unwrap();
         let engine_vault_start = zc::engine_ref(&f.slab.data).unwrap().vault;
         let vault_base_start = TokenAccount::unpack(&f.vault.data).unwrap().amount;
 
@@ -1675,7 +1746,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         // Read post-deposit state
         let vault_base = TokenAccount::unpack(&f.vault.data).unwrap().amount;
         let engine_vault_units = zc::engine_ref(&f.slab.data).unwrap().vault;
-        let dust_base = state::read_dust_base(&f.slab.data);
+        let dust_base = state::read_dust_base(&f.slab.data).unwrap();
 
         // Compute deltas
         let delta_vault_base = vault_base - vault_base_start;
@@ -1815,7 +1886,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         // Record pre-withdraw state
         let vault_base_before = TokenAccount::unpack(&f.vault.data).unwrap().amount;
         let engine_vault_before = zc::engine_ref(&f.slab.data).unwrap().vault;
-        let dust_before = state::read_dust_base(&f.slab.data);
+        let dust_before = state::read_dust_base(&f.slab.data).unwrap();
 
         // Withdraw 50 base tokens (aligned: 5 units)
         let mut vault_pda_account = TestAccount::new(f.vault_pda, Pubkey::default(), 0, vec![]);
@@ -1833,7 +1904,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         // Note: In test env, the SPL vault may not update due to CPI mock,
         // but engine state DOES update. We verify engine state consistency.
         let engine_vault_after = zc::engine_ref(&f.slab.data).unwrap().vault;
-        let dust_after = state::read_dust_base(&f.slab.data);
+        let dust_after = state::read_dust_base(&f.slab.data).unwrap();
 
         // Verify engine vault decreased by expected units
         assert_eq!(engine_vault_before - engine_vault_after, 5,
@@ -1896,7 +1967,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         }
 
         // Record pre-crank state
-        let dust_before_crank = state::read_dust_base(&f.slab.data);
+        let dust_before_crank = state::read_dust_base(&f.slab.data).unwrap();
         let engine_vault_before = zc::engine_ref(&f.slab.data).unwrap().vault;
         let insurance_before = zc::engine_ref(&f.slab.data).unwrap().insurance_fund.balance;
 
@@ -1910,7 +1981,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         }
 
         // Read post-crank state
-        let dust_after_crank = state::read_dust_base(&f.slab.data);
+        let dust_after_crank = state::read_dust_base(&f.slab.data).unwrap();
         let engine_vault_after = zc::engine_ref(&f.slab.data).unwrap().vault;
         let insurance_after = zc::engine_ref(&f.slab.data).unwrap().insurance_fund.balance;
         let vault_base = TokenAccount::unpack(&f.vault.data).unwrap().amount;
@@ -1979,7 +2050,7 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         }
 
         // Verify no dust created
-        let dust = state::read_dust_base(&f.slab.data);
+        let dust = state::read_dust_base(&f.slab.data).unwrap();
         assert_eq!(dust, 0, "Dust should be 0 when unit_scale=0: got {}", dust);
 
         // Verify INVARIANT #1: vault_base = engine_vault (scale=1) + dust (0)
@@ -2062,3 +2133,340 @@ fn encode_init_market_invert(fixture: &MarketFixture, crank_staleness: u64, inve
         let header = state::read_header(&f.slab.data);
         assert_eq!(header.magic, MAGIC, "Slab should still be initialized after failed close");
     }
+
+    #[test]
+    fn test_decode_truncated_data_rejected() {
+        // Deposit payload is u16 + u64; drop the last byte
+        let mut data = encode_deposit(0, 1000);
+        data.pop();
+        let res = percolator_prog::ix::Instruction::decode(&data);
+        assert_eq!(res.unwrap_err(), PercolatorError::TruncatedData.into());
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes_rejected() {
+        let mut data = encode_deposit(0, 1000);
+        data.push(0);
+        let res = percolator_prog::ix::Instruction::decode(&data);
+        assert_eq!(res.unwrap_err(), PercolatorError::TrailingBytes.into());
+
+        // Zero-payload variants must also be exact
+        let res = percolator_prog::ix::Instruction::decode(&[13u8, 0]);
+        assert_eq!(res.unwrap_err(), PercolatorError::TrailingBytes.into());
+    }
+
+    #[test]
+    fn test_decode_unknown_tag_rejected() {
+        let res = percolator_prog::ix::Instruction::decode(&[0xFF]);
+        assert_eq!(res.unwrap_err(), ProgramError::InvalidInstructionData);
+        let res = percolator_prog::ix::Instruction::decode(&[]);
+        assert_eq!(res.unwrap_err(), ProgramError::InvalidInstructionData);
+    }
+
+    #[test]
+    fn test_missing_account_rejected() {
+        let mut f = setup_market();
+        let data = encode_init_market(&f, 100);
+
+        // InitMarket requires 9 accounts; pass 8
+        let accounts = vec![
+            f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(), f.token_prog.to_info(),
+            f.clock.to_info(), f.rent.to_info(), f.system.to_info(),
+        ];
+        let res = process_instruction(&f.program_id, &accounts, &data);
+        assert_eq!(res, Err(PercolatorError::MissingAccount.into()));
+    }
+
+    thread_local! {
+        static RETURN_DATA: std::cell::RefCell<Vec<u8>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    /// Captures set_return_data per test thread; everything else uses the default stubs.
+    struct ReturnDataStubs;
+
+    impl solana_program::program_stubs::SyscallStubs for ReturnDataStubs {
+        fn sol_set_return_data(&self, data: &[u8]) {
+            RETURN_DATA.with(|r| *r.borrow_mut() = data.to_vec());
+        }
+    }
+
+    /// Installs ReturnDataStubs and puts the previous stubs back on drop, so
+    /// the override does not outlive the test.
+    struct ReturnDataStubsGuard(Option<Box<dyn solana_program::program_stubs::SyscallStubs>>);
+
+    impl ReturnDataStubsGuard {
+        fn install() -> Self {
+            Self(Some(solana_program::program_stubs::set_syscall_stubs(Box::new(ReturnDataStubs))))
+        }
+    }
+
+    impl Drop for ReturnDataStubsGuard {
+        fn drop(&mut self) {
+            if let Some(prev) = self.0.take() {
+                solana_program::program_stubs::set_syscall_stubs(prev);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trade_result_roundtrip() {
+        use percolator_prog::trade_result::TradeResult;
+        use percolator_prog::constants::{TRADE_RESULT_VERSION, TRADE_RESULT_LEN};
+
+        let r = TradeResult {
+            version: TRADE_RESULT_VERSION,
+            lp_idx: 3,
+            user_idx: 7,
+            exec_price_e6: 138_500_000,
+            exec_size: -42,
+            fee: 5,
+            user_position: -142,
+            user_entry_price_e6: 137_000_000,
+            oracle_price_e6: 138_000_000,
+            user_capital: 1_000_000,
+        };
+        let bytes = r.encode();
+        assert_eq!(bytes.len(), TRADE_RESULT_LEN);
+        assert_eq!(TradeResult::decode(&bytes), Some(r));
+
+        assert_eq!(TradeResult::decode(&bytes[..TRADE_RESULT_LEN - 1]), None);
+        let mut bad = bytes;
+        bad[0] = 0xFF;
+        assert_eq!(TradeResult::decode(&bad), None, "unknown version must not decode");
+    }
+
+    #[test]
+    fn test_trade_publishes_return_data() {
+        use percolator_prog::trade_result::TradeResult;
+        let _stubs = ReturnDataStubsGuard::install();
+
+        let mut f = setup_market();
+        let init_data = encode_init_market(&f, 100);
+        {
+            let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+            let init_accounts = vec![
+                f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(),
+                f.token_prog.to_info(), f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+            ];
+            process_instruction(&f.program_id, &init_accounts, &init_data).unwrap();
+        }
+
+        let mut user = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let mut user_ata = TestAccount::new(Pubkey::new_unique(), spl_token::ID, 0, make_token_account(f.mint.key, user.key, 1000)).writable();
+        {
+            let accounts = vec![user.to_info(), f.slab.to_info(), user_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_init_user(0)).unwrap();
+        }
+        let user_idx = find_idx_by_owner(&f.slab.data, user.key).unwrap();
+        {
+            let accounts = vec![user.to_info(), f.slab.to_info(), user_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_deposit(user_idx, 1000)).unwrap();
+        }
+
+        let mut lp = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let mut lp_ata = TestAccount::new(Pubkey::new_unique(), spl_token::ID, 0, make_token_account(f.mint.key, lp.key, 1000)).writable();
+        {
+            let accs = vec![lp.to_info(), f.slab.to_info(), lp_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accs, &encode_init_lp(Pubkey::new_unique(), Pubkey::new_unique(), 0)).unwrap();
+        }
+        let lp_idx = find_idx_by_owner(&f.slab.data, lp.key).unwrap();
+        {
+            let accounts = vec![lp.to_info(), f.slab.to_info(), lp_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_deposit(lp_idx, 1000)).unwrap();
+        }
+
+        // The fixture market is fee-free; charge 10 bps so the fee is observable
+        zc::engine_mut(&mut f.slab.data).unwrap().params.trading_fee_bps = 10;
+        let fee_revenue_before = zc::engine_ref(&f.slab.data).unwrap().insurance_fund.fee_revenue;
+        RETURN_DATA.with(|r| r.borrow_mut().clear());
+        {
+            let accounts = vec![user.to_info(), lp.to_info(), f.slab.to_info(), f.clock.to_info(), f.pyth_index.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_trade(lp_idx, user_idx, 100)).unwrap();
+        }
+
+        let data = RETURN_DATA.with(|r| r.borrow().clone());
+        let result = TradeResult::decode(&data).expect("trade should publish a result");
+        let engine = zc::engine_ref(&f.slab.data).unwrap();
+        let acc = &engine.accounts[user_idx as usize];
+        assert_eq!(result.lp_idx, lp_idx);
+        assert_eq!(result.user_idx, user_idx);
+        assert_eq!(result.exec_size, 100);
+        assert_eq!(result.exec_price_e6, result.oracle_price_e6, "NoCpi fills at oracle");
+        assert_eq!(result.user_position, acc.position_size);
+        assert_eq!(result.user_entry_price_e6, acc.entry_price);
+        assert_eq!(result.user_capital, acc.capital);
+        assert!(result.fee > 0, "trade pays a fee");
+        assert_eq!(result.fee, engine.insurance_fund.fee_revenue - fee_revenue_before, "fee as booked by the engine");
+    }
+
+    #[test]
+    fn test_reduce_only_market() {
+        let mut f = setup_market();
+        let init_data = encode_init_market(&f, 100);
+        {
+            let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+            let init_accounts = vec![
+                f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(),
+                f.token_prog.to_info(), f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+            ];
+            process_instruction(&f.program_id, &init_accounts, &init_data).unwrap();
+        }
+
+        let mut user = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let mut user_ata = TestAccount::new(Pubkey::new_unique(), spl_token::ID, 0, make_token_account(f.mint.key, user.key, 1000)).writable();
+        {
+            let accounts = vec![user.to_info(), f.slab.to_info(), user_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_init_user(0)).unwrap();
+        }
+        let user_idx = find_idx_by_owner(&f.slab.data, user.key).unwrap();
+        {
+            let accounts = vec![user.to_info(), f.slab.to_info(), user_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_deposit(user_idx, 1000)).unwrap();
+        }
+
+        let mut lp = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let mut lp_ata = TestAccount::new(Pubkey::new_unique(), spl_token::ID, 0, make_token_account(f.mint.key, lp.key, 1000)).writable();
+        {
+            let accs = vec![lp.to_info(), f.slab.to_info(), lp_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accs, &encode_init_lp(Pubkey::new_unique(), Pubkey::new_unique(), 0)).unwrap();
+        }
+        let lp_idx = find_idx_by_owner(&f.slab.data, lp.key).unwrap();
+        {
+            let accounts = vec![lp.to_info(), f.slab.to_info(), lp_ata.to_info(), f.vault.to_info(), f.token_prog.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_deposit(lp_idx, 1000)).unwrap();
+        }
+
+        let mut trade = |f: &mut MarketFixture, size: i128| {
+            let accounts = vec![user.to_info(), lp.to_info(), f.slab.to_info(), f.clock.to_info(), f.pyth_index.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_trade(lp_idx, user_idx, size))
+        };
+        trade(&mut f, 100).unwrap();
+
+        // Only the admin may toggle the flag
+        let mut attacker = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        {
+            let accounts = vec![attacker.to_info(), f.slab.to_info()];
+            let res = process_instruction(&f.program_id, &accounts, &encode_set_reduce_only(1));
+            assert_eq!(res, Err(PercolatorError::EngineUnauthorized.into()));
+        }
+        {
+            let accounts = vec![f.admin.to_info(), f.slab.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_set_reduce_only(1)).unwrap();
+        }
+        assert_eq!(state::read_header(&f.slab.data).flags, 1);
+
+        assert_eq!(trade(&mut f, 10), Err(PercolatorError::MarketReduceOnly.into()), "growing rejected");
+        assert_eq!(trade(&mut f, -150), Err(PercolatorError::MarketReduceOnly.into()), "flipping rejected");
+        trade(&mut f, -40).unwrap();
+        let engine = zc::engine_ref(&f.slab.data).unwrap();
+        assert_eq!(engine.accounts[user_idx as usize].position_size, 60);
+
+        {
+            let accounts = vec![f.admin.to_info(), f.slab.to_info()];
+            process_instruction(&f.program_id, &accounts, &encode_set_reduce_only(0)).unwrap();
+        }
+        trade(&mut f, 10).unwrap();
+    }
+
+    #[test]
+    fn test_le_cursor_reads() {
+        use percolator_prog::le::{read_u8, read_u16, read_i128, read_pubkey};
+
+        let key = Pubkey::new_unique();
+        let mut buf = vec![0xAB];
+        buf.extend_from_slice(&0x1234u16.to_le_bytes());
+        buf.extend_from_slice(&(-5i128).to_le_bytes());
+        buf.extend_from_slice(key.as_ref());
+
+        let mut cur = &buf[..];
+        assert_eq!(read_u8(&mut cur).unwrap(), 0xAB);
+        assert_eq!(read_u16(&mut cur).unwrap(), 0x1234);
+        assert_eq!(read_i128(&mut cur).unwrap(), -5);
+        assert_eq!(read_pubkey(&mut cur).unwrap(), key);
+        assert!(cur.is_empty());
+
+        // A short read fails and leaves the cursor untouched
+        let mut short = &buf[..10];
+        assert_eq!(read_i128(&mut short), Err(PercolatorError::TruncatedData.into()));
+        assert_eq!(short.len(), 10);
+        assert_eq!(read_u8(&mut &[][..]), Err(PercolatorError::TruncatedData.into()));
+    }
+
+    #[test]
+    fn test_set_oracle_params() {
+        let mut f = setup_market();
+        let init_data = encode_init_market(&f, 100);
+        {
+            let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+            let init_accounts = vec![
+                f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(),
+                f.token_prog.to_info(), f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+            ];
+            process_instruction(&f.program_id, &init_accounts, &init_data).unwrap();
+        }
+
+        let mut caller = TestAccount::new(Pubkey::new_unique(), solana_program::system_program::id(), 0, vec![]).signer();
+        let crank = |f: &mut MarketFixture, caller: &mut TestAccount| {
+            let accs = vec![caller.to_info(), f.slab.to_info(), f.clock.to_info(), f.pyth_index.to_info()];
+            process_instruction(&f.program_id, &accs, &encode_crank_permissionless(0))
+        };
+        let set = |f: &mut MarketFixture, who: &mut TestAccount, secs: u64, bps: u16| {
+            let accs = vec![who.to_info(), f.slab.to_info()];
+            process_instruction(&f.program_id, &accs, &encode_set_oracle_params(secs, bps))
+        };
+
+        // Price published at t=100, now t=150: fresh under the initial 100s limit
+        f.clock.data = make_clock(101, 150);
+        crank(&mut f, &mut caller).unwrap();
+
+        // Only the admin may change oracle limits, and they must be sane
+        assert_eq!(set(&mut f, &mut caller, 10, 500), Err(PercolatorError::EngineUnauthorized.into()));
+        let mut admin = TestAccount::new(f.admin.key, solana_program::system_program::id(), 0, vec![]).signer();
+        assert_eq!(set(&mut f, &mut admin, 0, 500), Err(PercolatorError::InvalidConfigParam.into()));
+        assert_eq!(set(&mut f, &mut admin, 10, 10_001), Err(PercolatorError::InvalidConfigParam.into()));
+
+        set(&mut f, &mut admin, 10, 500).unwrap();
+        let config = state::read_config(&f.slab.data);
+        assert_eq!(config.max_staleness_secs, 10);
+        assert_eq!(config.conf_filter_bps, 500);
+
+        // The same 50s-old price is now stale
+        f.clock.data = make_clock(102, 150);
+        assert_eq!(crank(&mut f, &mut caller), Err(PercolatorError::OracleStale.into()));
+
+        // Fresh enough again, but conf (1) exceeds a 0 bps band
+        set(&mut f, &mut admin, 100, 0).unwrap();
+        assert_eq!(crank(&mut f, &mut caller), Err(PercolatorError::OracleConfTooWide.into()));
+
+        set(&mut f, &mut admin, 100, 500).unwrap();
+        crank(&mut f, &mut caller).unwrap();
+    }
+
+    #[test]
+    fn test_init_market_is_idempotent() {
+        let mut f = setup_market();
+        let mut dummy_ata = TestAccount::new(Pubkey::new_unique(), Pubkey::default(), 0, vec![]);
+        let mut init = |f: &mut MarketFixture, data: &[u8]| {
+            let accounts = vec![
+                f.admin.to_info(), f.slab.to_info(), f.mint.to_info(), f.vault.to_info(), f.token_prog.to_info(),
+                f.clock.to_info(), f.rent.to_info(), dummy_ata.to_info(), f.system.to_info(),
+            ];
+            process_instruction(&f.program_id, &accounts, data)
+        };
+
+        let same = encode_init_market(&f, 100);
+        let other_staleness = encode_init_market(&f, 200);
+        let other_invert = encode_init_market_invert(&f, 100, 1, 0);
+
+        init(&mut f, &same).unwrap();
+        let before = f.slab.data.clone();
+
+        // Same parameters: succeeds and leaves the slab untouched
+        init(&mut f, &same).unwrap();
+        assert_eq!(f.slab.data, before);
+
+        // Any differing parameter is still rejected
+        assert_eq!(init(&mut f, &other_staleness), Err(PercolatorError::AlreadyInitialized.into()));
+        assert_eq!(init(&mut f, &other_invert), Err(PercolatorError::AlreadyInitialized.into()));
+        assert_eq!(f.slab.data, before);
+    }
@@ -245,6 +245,21 @@ impl DeferredAdl {
     };
 }
 
+/// Balances captured before an operation for the `debug-invariants` cash
+/// check. Empty when the feature is off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CashSnapshot {
+    /// sum(capital) + sum(pnl); funding not yet settled is excluded
+    #[cfg(feature = "debug-invariants")]
+    accounts: i128,
+    #[cfg(feature = "debug-invariants")]
+    insurance: u128,
+    #[cfg(feature = "debug-invariants")]
+    fee_revenue: u128,
+    #[cfg(feature = "debug-invariants")]
+    loss_accum: u128,
+}
+
 /// Risk engine parameters
 #[repr(C)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -433,6 +448,24 @@ pub struct RiskEngine {
     /// Total number of force-realize closes performed (lifetime)
     pub lifetime_force_realize_closes: u64,
 
+    /// Total traded notional across all fills (lifetime)
+    pub lifetime_volume: u128,
+
+    // ========================================
+    // Sweep Summary Base (lifetime totals at last completed sweep)
+    // ========================================
+    /// insurance_fund.fee_revenue when the last full sweep completed
+    pub sweep_base_fee_revenue: u128,
+
+    /// funding_index_qpb_e6 when the last full sweep completed
+    pub sweep_base_funding_index: i128,
+
+    /// lifetime_volume when the last full sweep completed
+    pub sweep_base_volume: u128,
+
+    /// lifetime_liquidations when the last full sweep completed
+    pub sweep_base_liquidations: u64,
+
     // ========================================
     // LP Aggregates (O(1) maintained for funding/threshold)
     // ========================================
@@ -546,6 +579,71 @@ pub struct CrankOutcome {
     pub force_realize_closed: u16,
     /// Number of force-realize errors during this crank
     pub force_realize_errors: u16,
+    /// Per-sweep totals, set only by the crank that completes a full sweep
+    pub sweep_summary: Option<SweepSummary>,
+}
+
+/// Totals accumulated between two consecutive full-sweep completions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SweepSummary {
+    /// Trading and liquidation fees collected
+    pub fee_revenue: u128,
+    /// Change in the global funding index
+    pub funding_index_delta: i128,
+    /// Traded notional
+    pub volume: u128,
+    /// Liquidations performed
+    pub liquidations: u64,
+}
+
+/// Hypothetical fill evaluated with the same checks and math as execute_trade
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradePreview {
+    /// Position after the fill
+    pub new_position: i128,
+    /// Entry price after the fill
+    pub new_entry_price: u64,
+    /// Trading fee charged on the fill
+    pub fee: u128,
+    /// PnL realized by the closing part of the fill (before fee)
+    pub realized_pnl: i128,
+    /// MTM equity at oracle after the fill
+    pub equity: u128,
+    /// Maintenance margin required for the new position
+    pub maintenance_required: u128,
+    /// Initial margin required for the new position
+    pub initial_required: u128,
+    /// LP position after the fill
+    pub lp_new_position: i128,
+    /// LP MTM equity at oracle after the fill
+    pub lp_equity: u128,
+    /// Maintenance margin required for the LP's new position
+    pub lp_maintenance_required: u128,
+    /// Whether execute_trade's margin checks would accept both sides
+    pub passes_maintenance: bool,
+}
+
+/// Hypothetical liquidation evaluated with the same math as liquidate_at_oracle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidationPreview {
+    /// MTM equity at oracle before liquidation
+    pub equity: u128,
+    /// Maintenance margin required for the current position
+    pub maintenance_required: u128,
+    /// Absolute position closed (including the full-close fallback)
+    pub close_abs: u128,
+    /// Whether the whole position would be closed
+    pub full_close: bool,
+    /// Position left after liquidation
+    pub remaining_position: i128,
+    /// Mark PnL realized on the closed part at oracle
+    pub realized_pnl: i128,
+    /// Liquidation fee actually paid to insurance (capped by capital)
+    pub fee: u128,
+    /// Capital left after loss settlement and fee
+    pub capital_after: u128,
+    /// Loss exceeding the account's capital, socialized via ADL
+    pub deficit: u128,
 }
 
 // ============================================================================
@@ -743,6 +841,11 @@ impl RiskEngine {
             crank_step: 0,
             lifetime_liquidations: 0,
             lifetime_force_realize_closes: 0,
+            lifetime_volume: 0,
+            sweep_base_fee_revenue: 0,
+            sweep_base_funding_index: 0,
+            sweep_base_volume: 0,
+            sweep_base_liquidations: 0,
             net_lp_pos: 0,
             lp_sum_abs: 0,
             lp_max_abs: 0,
@@ -1419,7 +1522,10 @@ impl RiskEngine {
         }
 
         // Accrue funding first (always) - propagate errors, don't continue with corrupt state
+        // Accrual only moves the global index; accounts see funding when touched
+        let cash_before = self.debug_cash_snapshot();
         self.accrue_funding(now_slot, oracle_price, funding_rate_bps_per_slot)?;
+        self.debug_check_cash("accrue_funding", cash_before, 0);
 
         // Check if we're advancing the global crank slot
         let advanced = now_slot > self.last_crank_slot;
@@ -1504,6 +1610,7 @@ impl RiskEngine {
         }
 
         // Advance crank step; when completing final step, record completion and wrap
+        let mut sweep_summary = None;
         self.crank_step += 1;
         if self.crank_step == NUM_STEPS {
             // Full sweep complete - finalize pending now that all accounts have been scanned
@@ -1515,8 +1622,13 @@ impl RiskEngine {
             self.last_full_sweep_completed_slot = now_slot;
             // Commit bounded lp_max_abs from sweep
             self.lp_max_abs = self.lp_max_abs_sweep;
+            sweep_summary = Some(self.take_sweep_summary());
         }
 
+        // Deferred ADL sits in pending buckets until the sweep completes, so
+        // value conservation is only checked at sweep boundaries
+        self.debug_check_invariants("keeper_crank", self.crank_step == 0);
+
         Ok(CrankOutcome {
             advanced,
             slots_forgiven,
@@ -1528,9 +1640,32 @@ impl RiskEngine {
             num_gc_closed,
             force_realize_closed,
             force_realize_errors,
+            sweep_summary,
         })
     }
 
+    /// Diff lifetime totals against the last sweep's base, then move the base forward
+    fn take_sweep_summary(&mut self) -> SweepSummary {
+        let summary = SweepSummary {
+            fee_revenue: self
+                .insurance_fund
+                .fee_revenue
+                .saturating_sub(self.sweep_base_fee_revenue),
+            funding_index_delta: self
+                .funding_index_qpb_e6
+                .saturating_sub(self.sweep_base_funding_index),
+            volume: self.lifetime_volume.saturating_sub(self.sweep_base_volume),
+            liquidations: self
+                .lifetime_liquidations
+                .saturating_sub(self.sweep_base_liquidations),
+        };
+        self.sweep_base_fee_revenue = self.insurance_fund.fee_revenue;
+        self.sweep_base_funding_index = self.funding_index_qpb_e6;
+        self.sweep_base_volume = self.lifetime_volume;
+        self.sweep_base_liquidations = self.lifetime_liquidations;
+        summary
+    }
+
     // ========================================
     // Liquidation
     // ========================================
@@ -1695,11 +1830,12 @@ impl RiskEngine {
 
         // Update position: reduce by close_abs (maintain sign)
         let new_abs_pos = current_abs_pos.saturating_sub(close_abs);
-        self.accounts[idx as usize].position_size = if pos > 0 {
+        let new_pos = if pos > 0 {
             new_abs_pos as i128
         } else {
             -(new_abs_pos as i128)
         };
+        self.accounts[idx as usize].position_size = new_pos;
 
         // Entry price remains unchanged for remaining position
         // (partial close at oracle price doesn't change the entry of what remains)
@@ -1707,6 +1843,16 @@ impl RiskEngine {
         // Update OI
         self.total_open_interest = self.total_open_interest.saturating_sub(close_abs);
 
+        // Update LP aggregates if this is an LP account (O(1))
+        if self.accounts[idx as usize].is_lp() {
+            // Partial close: delta = new_pos - old_pos
+            self.net_lp_pos = self.net_lp_pos
+                .saturating_sub(pos)
+                .saturating_add(new_pos);
+            self.lp_sum_abs = self.lp_sum_abs.saturating_sub(close_abs);
+            // lp_max_abs: can't decrease without full scan, leave as conservative upper bound
+        }
+
         // Route positive mark_pnl through ADL (excluding this account - it shouldn't fund its own profit)
         if mark_pnl > 0 {
             self.apply_adl_excluding(mark_pnl as u128, idx as usize)?;
@@ -1790,6 +1936,13 @@ impl RiskEngine {
         // Update OI (remove this account's contribution)
         self.total_open_interest = self.total_open_interest.saturating_sub(abs_pos);
 
+        // Update LP aggregates if this is an LP account (O(1))
+        if self.accounts[idx as usize].is_lp() {
+            self.net_lp_pos = self.net_lp_pos.saturating_sub(pos);
+            self.lp_sum_abs = self.lp_sum_abs.saturating_sub(abs_pos);
+            // lp_max_abs: can't decrease without full scan, leave as conservative upper bound
+        }
+
         // Route positive mark_pnl through ADL (excluding this account - it shouldn't fund its own profit)
         if mark_pnl > 0 {
             self.apply_adl_excluding(mark_pnl as u128, idx as usize)?;
@@ -2179,6 +2332,9 @@ impl RiskEngine {
             return Ok(false);
         }
 
+        let cash_before = self.debug_cash_snapshot();
+        let funding_settled = self.debug_pending_funding(idx);
+
         // Settle funding + best-effort fees (can't block on margin - we're liquidating)
         self.touch_account_for_liquidation(idx, now_slot)?;
 
@@ -2213,6 +2369,7 @@ impl RiskEngine {
         if !outcome.position_was_closed {
             return Ok(false);
         }
+        let mut realized_mark = outcome.mark_pnl;
 
         // Post-liquidation safety check: if position remains and still below target,
         // fall back to full close. This handles rare cases where mark_pnl realization
@@ -2227,6 +2384,7 @@ impl RiskEngine {
                     self.oracle_close_position_core_deferred_adl(idx, oracle_price)?;
                 if fallback_outcome.position_was_closed {
                     outcome.abs_pos = outcome.abs_pos.saturating_add(fallback_outcome.abs_pos);
+                    realized_mark = realized_mark.saturating_add(fallback_outcome.mark_pnl);
                     // Accumulate deferred ADL amounts
                     deferred.profit_to_fund = deferred.profit_to_fund
                         .saturating_add(fallback_deferred.profit_to_fund);
@@ -2267,6 +2425,15 @@ impl RiskEngine {
         // Recompute warmup reserved after insurance changes
         self.recompute_warmup_insurance_reserved();
 
+        // Expected: settled funding, plus mark PnL realized without a
+        // counterparty, minus the profit ADL funds out of other accounts,
+        // insurance or loss_accum. An unpaid loss is clamped off the account
+        // and charged through ADL, which cancels; the fee is capital to insurance.
+        let expected = funding_settled
+            .saturating_add(realized_mark)
+            .saturating_sub(u128_to_i128_clamped(deferred.profit_to_fund));
+        self.debug_check_cash("liquidate_at_oracle", cash_before, expected);
+        self.debug_check_invariants("liquidate_at_oracle", true);
         Ok(true)
     }
 
@@ -3106,19 +3273,334 @@ impl RiskEngine {
     /// FAIL-SAFE: On overflow, returns 0 (worst-case equity) to ensure liquidation
     /// can still trigger. This prevents overflow from blocking liquidation.
     pub fn account_equity_mtm_at_oracle(&self, account: &Account, oracle_price: u64) -> u128 {
-        let mark = match Self::mark_pnl_for_position(
+        Self::equity_mtm_parts(
+            account.capital,
+            account.pnl,
             account.position_size,
             account.entry_price,
             oracle_price,
-        ) {
+        )
+    }
+
+    /// MTM equity from raw account fields (fail-safe: overflow => 0).
+    fn equity_mtm_parts(capital: u128, pnl: i128, position: i128, entry: u64, oracle_price: u64) -> u128 {
+        let mark = match Self::mark_pnl_for_position(position, entry, oracle_price) {
             Ok(m) => m,
             Err(_) => return 0, // Overflow => worst-case equity
         };
-        let cap_i = u128_to_i128_clamped(account.capital);
-        let eq_i = cap_i.saturating_add(account.pnl).saturating_add(mark);
+        let cap_i = u128_to_i128_clamped(capital);
+        let eq_i = cap_i.saturating_add(pnl).saturating_add(mark);
         if eq_i > 0 { eq_i as u128 } else { 0 }
     }
 
+    /// Margin required for a position at oracle price and the given bps.
+    fn margin_required_for(position: i128, oracle_price: u64, bps: u64) -> u128 {
+        let position_value =
+            mul_u128(saturating_abs_i128(position) as u128, oracle_price as u128) / 1_000_000;
+        mul_u128(position_value, bps as u128) / 10_000
+    }
+
+    /// User-side fill: new position, new entry price and realized PnL (before fee)
+    /// after taking `exec_size` at `exec_price`.
+    fn user_fill(position: i128, entry: u64, exec_price: u64, exec_size: i128) -> Result<(i128, u64, i128)> {
+        // PNL from closing existing position
+        let mut realized = 0i128;
+        if (position > 0 && exec_size < 0) || (position < 0 && exec_size > 0) {
+            let close_size = core::cmp::min(
+                saturating_abs_i128(position),
+                saturating_abs_i128(exec_size),
+            );
+            let price_diff = if position > 0 {
+                (exec_price as i128).saturating_sub(entry as i128)
+            } else {
+                (entry as i128).saturating_sub(exec_price as i128)
+            };
+
+            // Use saturating arithmetic (no overflow errors needed with Solana atomicity)
+            realized = price_diff
+                .saturating_mul(close_size)
+                .saturating_div(1_000_000);
+        }
+
+        let new_position = position.saturating_add(exec_size);
+
+        let mut new_entry = entry;
+        if (position > 0 && exec_size > 0) || (position < 0 && exec_size < 0) {
+            let old_notional = mul_u128(saturating_abs_i128(position) as u128, entry as u128);
+            let new_notional = mul_u128(saturating_abs_i128(exec_size) as u128, exec_price as u128);
+            let total_notional = add_u128(old_notional, new_notional);
+            let total_size = saturating_abs_i128(position)
+                .saturating_add(saturating_abs_i128(exec_size));
+            if total_size != 0 {
+                new_entry = div_u128(total_notional, total_size as u128)? as u64;
+            }
+        } else if saturating_abs_i128(position) < saturating_abs_i128(exec_size) {
+            new_entry = exec_price;
+        }
+
+        Ok((new_position, new_entry, realized))
+    }
+
+    /// LP-side fill: new position and entry price after the LP takes the
+    /// opposite of a user's `exec_size` at `exec_price`.
+    fn lp_fill(position: i128, entry: u64, exec_price: u64, exec_size: i128) -> Result<(i128, u64)> {
+        let new_position = position.saturating_sub(exec_size);
+        let mut new_entry = entry;
+
+        // Bug #8 fix: Always update entry on sign flip, regardless of abs comparison
+        let sign_flip = (position > 0 && new_position < 0) || (position < 0 && new_position > 0);
+
+        if position == 0 {
+            new_entry = exec_price;
+        } else if sign_flip && new_position != 0 {
+            // Bug #8 fix: On any sign flip with nonzero new position, use exec_price
+            new_entry = exec_price;
+        } else if (position > 0 && new_position > position)
+            || (position < 0 && new_position < position)
+        {
+            // Position expanding in same direction: weighted average entry
+            let old_notional = mul_u128(saturating_abs_i128(position) as u128, entry as u128);
+            let new_notional = mul_u128(saturating_abs_i128(exec_size) as u128, exec_price as u128);
+            let total_notional = add_u128(old_notional, new_notional);
+            let total_size = saturating_abs_i128(position)
+                .saturating_add(saturating_abs_i128(exec_size));
+            if total_size != 0 {
+                new_entry = div_u128(total_notional, total_size as u128)? as u64;
+            }
+        }
+
+        Ok((new_position, new_entry))
+    }
+
+    /// Pre-match checks shared by execute_trade and preview_trade: crank
+    /// freshness, account kinds, and risk-increase gating on `size`.
+    fn check_trade_gates(
+        &self,
+        lp_idx: u16,
+        user_idx: u16,
+        now_slot: u64,
+        oracle_price: u64,
+        size: i128,
+    ) -> Result<()> {
+        // Require fresh crank (time-based) before state-changing operations
+        self.require_fresh_crank(now_slot)?;
+
+        // Validate indices
+        if !self.is_used(lp_idx as usize) || !self.is_used(user_idx as usize) {
+            return Err(RiskError::AccountNotFound);
+        }
+
+        // Validate oracle price bounds (prevents overflow in mark_pnl calculations)
+        if oracle_price == 0 || oracle_price > MAX_ORACLE_PRICE {
+            return Err(RiskError::Overflow);
+        }
+
+        // Validate account kinds (using is_lp/is_user methods for SBF workaround)
+        if !self.accounts[lp_idx as usize].is_lp() {
+            return Err(RiskError::AccountKindMismatch);
+        }
+        if !self.accounts[user_idx as usize].is_user() {
+            return Err(RiskError::AccountKindMismatch);
+        }
+
+        // Check if trade increases risk (absolute exposure for either party)
+        let old_user_pos = self.accounts[user_idx as usize].position_size;
+        let old_lp_pos = self.accounts[lp_idx as usize].position_size;
+        let new_user_pos = old_user_pos.saturating_add(size);
+        let new_lp_pos = old_lp_pos.saturating_sub(size);
+
+        let user_inc = saturating_abs_i128(new_user_pos) > saturating_abs_i128(old_user_pos);
+        let lp_inc = saturating_abs_i128(new_lp_pos) > saturating_abs_i128(old_lp_pos);
+
+        if user_inc || lp_inc {
+            // Risk-increasing: require recent full sweep
+            self.require_recent_full_sweep(now_slot)?;
+            self.enforce_op(OpClass::RiskIncrease)
+        } else {
+            self.enforce_op(OpClass::RiskReduce)
+        }
+    }
+
+    /// Evaluate a hypothetical fill between a user and an LP with the same
+    /// checks and math execute_trade uses, without touching state. Gating
+    /// failures (stale crank, wrong account kinds, risk-increase limits,
+    /// position bounds) return the error execute_trade would; margin results
+    /// are reported in the preview. Funding and maintenance fees that
+    /// execute_trade would settle first are not applied, so call this on
+    /// freshly touched accounts for exact numbers.
+    pub fn preview_trade(
+        &self,
+        lp_idx: u16,
+        user_idx: u16,
+        now_slot: u64,
+        oracle_price: u64,
+        exec_price: u64,
+        exec_size: i128,
+    ) -> Result<TradePreview> {
+        self.check_trade_gates(lp_idx, user_idx, now_slot, oracle_price, exec_size)?;
+        if exec_price == 0 || exec_price > MAX_ORACLE_PRICE {
+            return Err(RiskError::Overflow);
+        }
+        if saturating_abs_i128(exec_size) as u128 > MAX_POSITION_ABS {
+            return Err(RiskError::Overflow);
+        }
+        let user = &self.accounts[user_idx as usize];
+        let lp = &self.accounts[lp_idx as usize];
+
+        let notional =
+            mul_u128(saturating_abs_i128(exec_size) as u128, exec_price as u128) / 1_000_000;
+        let fee = mul_u128(notional, self.params.trading_fee_bps as u128) / 10_000;
+
+        let (new_position, new_entry_price, realized_pnl) =
+            Self::user_fill(user.position_size, user.entry_price, exec_price, exec_size)?;
+        let (lp_new_position, lp_new_entry_price) =
+            Self::lp_fill(lp.position_size, lp.entry_price, exec_price, exec_size)?;
+        if saturating_abs_i128(new_position) as u128 > MAX_POSITION_ABS
+            || saturating_abs_i128(lp_new_position) as u128 > MAX_POSITION_ABS
+        {
+            return Err(RiskError::Overflow);
+        }
+        let new_pnl = user.pnl.saturating_add(realized_pnl).saturating_sub(fee as i128);
+        let lp_new_pnl = lp.pnl.saturating_sub(realized_pnl);
+
+        let equity =
+            Self::equity_mtm_parts(user.capital, new_pnl, new_position, new_entry_price, oracle_price);
+        let maintenance_required = Self::margin_required_for(
+            new_position,
+            oracle_price,
+            self.params.maintenance_margin_bps,
+        );
+        let initial_required =
+            Self::margin_required_for(new_position, oracle_price, self.params.initial_margin_bps);
+        let lp_equity = Self::equity_mtm_parts(
+            lp.capital,
+            lp_new_pnl,
+            lp_new_position,
+            lp_new_entry_price,
+            oracle_price,
+        );
+        let lp_maintenance_required = Self::margin_required_for(
+            lp_new_position,
+            oracle_price,
+            self.params.maintenance_margin_bps,
+        );
+
+        Ok(TradePreview {
+            new_position,
+            new_entry_price,
+            fee,
+            realized_pnl,
+            equity,
+            maintenance_required,
+            initial_required,
+            lp_new_position,
+            lp_equity,
+            lp_maintenance_required,
+            passes_maintenance: (new_position == 0 || equity > maintenance_required)
+                && (lp_new_position == 0 || lp_equity > lp_maintenance_required),
+        })
+    }
+
+    /// Evaluate liquidate_at_oracle for an account without touching state.
+    /// Returns None if the account would not be liquidated. As with
+    /// preview_trade, funding and fees the liquidation touch would settle
+    /// first are not applied, and ADL effects on other accounts are not
+    /// modelled beyond reporting the deficit.
+    pub fn preview_liquidation(
+        &self,
+        idx: u16,
+        oracle_price: u64,
+    ) -> Result<Option<LiquidationPreview>> {
+        if (idx as usize) >= MAX_ACCOUNTS || !self.is_used(idx as usize) {
+            return Err(RiskError::AccountNotFound);
+        }
+        if oracle_price == 0 || oracle_price > MAX_ORACLE_PRICE {
+            return Err(RiskError::Overflow);
+        }
+
+        let account = self.accounts[idx as usize];
+        if account.position_size == 0 || self.is_above_maintenance_margin_mtm(&account, oracle_price) {
+            return Ok(None);
+        }
+        let (close_abs, is_full_close) = self.compute_liquidation_close_amount(&account, oracle_price);
+        if close_abs == 0 {
+            return Ok(None);
+        }
+
+        let mut after = account;
+        let (mut realized_pnl, mut deficit) = Self::close_slice_on_copy(&mut after, oracle_price, close_abs);
+        let mut closed = close_abs;
+
+        // Same post-liquidation fallback as liquidate_at_oracle
+        if after.position_size != 0 {
+            let target_bps = self.params.maintenance_margin_bps
+                .saturating_add(self.params.liquidation_buffer_bps);
+            if !self.is_above_margin_bps_mtm(&after, oracle_price, target_bps) {
+                let rest = saturating_abs_i128(after.position_size) as u128;
+                let (pnl, unpaid) = Self::close_slice_on_copy(&mut after, oracle_price, rest);
+                realized_pnl = realized_pnl.saturating_add(pnl);
+                deficit = deficit.saturating_add(unpaid);
+                closed = closed.saturating_add(rest);
+            }
+        }
+
+        let notional = mul_u128(closed, oracle_price as u128) / 1_000_000;
+        let fee_raw = mul_u128(notional, self.params.liquidation_fee_bps as u128) / 10_000;
+        let fee = core::cmp::min(core::cmp::min(fee_raw, self.params.liquidation_fee_cap), after.capital);
+
+        Ok(Some(LiquidationPreview {
+            equity: self.account_equity_mtm_at_oracle(&account, oracle_price),
+            maintenance_required: Self::margin_required_for(
+                account.position_size,
+                oracle_price,
+                self.params.maintenance_margin_bps,
+            ),
+            close_abs: closed,
+            full_close: is_full_close || after.position_size == 0,
+            remaining_position: after.position_size,
+            realized_pnl,
+            fee,
+            capital_after: after.capital.saturating_sub(fee),
+            deficit,
+        }))
+    }
+
+    /// Close `close_abs` of a copied account at oracle the way the close cores
+    /// settle it: mark PnL on the slice, losses paid from capital, anything
+    /// beyond capital returned as unpaid. Returns (mark_pnl, unpaid).
+    fn close_slice_on_copy(account: &mut Account, oracle_price: u64, close_abs: u128) -> (i128, u128) {
+        let pos = account.position_size;
+        let abs_pos = saturating_abs_i128(pos) as u128;
+        let close_abs = core::cmp::min(close_abs, abs_pos);
+
+        let diff: i128 = if pos > 0 {
+            (oracle_price as i128).saturating_sub(account.entry_price as i128)
+        } else {
+            (account.entry_price as i128).saturating_sub(oracle_price as i128)
+        };
+        let mark_pnl = diff
+            .checked_mul(close_abs as i128)
+            .and_then(|v| v.checked_div(1_000_000))
+            .unwrap_or(-u128_to_i128_clamped(account.capital));
+
+        let new_abs = abs_pos - close_abs;
+        account.position_size = if pos > 0 { new_abs as i128 } else { -(new_abs as i128) };
+        account.pnl = account.pnl.saturating_add(mark_pnl);
+
+        let mut unpaid = 0;
+        if account.pnl < 0 {
+            let loss = neg_i128_to_u128(account.pnl);
+            let pay = core::cmp::min(loss, account.capital);
+            account.capital -= pay;
+            account.pnl = account.pnl.saturating_add(pay as i128);
+            if account.pnl < 0 {
+                unpaid = neg_i128_to_u128(account.pnl);
+                account.pnl = 0;
+            }
+        }
+        (mark_pnl, unpaid)
+    }
+
     /// MTM margin check: is equity_mtm > required margin?
     /// This is the ONLY correct margin predicate for all risk checks.
     ///
@@ -3216,43 +3698,14 @@ impl RiskEngine {
         oracle_price: u64,
         size: i128,
     ) -> Result<()> {
-        // Require fresh crank (time-based) before state-changing operations
-        self.require_fresh_crank(now_slot)?;
-
-        // Validate indices
-        if !self.is_used(lp_idx as usize) || !self.is_used(user_idx as usize) {
-            return Err(RiskError::AccountNotFound);
-        }
-
-        // Validate oracle price bounds (prevents overflow in mark_pnl calculations)
-        if oracle_price == 0 || oracle_price > MAX_ORACLE_PRICE {
-            return Err(RiskError::Overflow);
-        }
-
-        // Validate account kinds (using is_lp/is_user methods for SBF workaround)
-        if !self.accounts[lp_idx as usize].is_lp() {
-            return Err(RiskError::AccountKindMismatch);
-        }
-        if !self.accounts[user_idx as usize].is_user() {
-            return Err(RiskError::AccountKindMismatch);
-        }
-
-        // Check if trade increases risk (absolute exposure for either party)
+        self.check_trade_gates(lp_idx, user_idx, now_slot, oracle_price, size)?;
         let old_user_pos = self.accounts[user_idx as usize].position_size;
         let old_lp_pos = self.accounts[lp_idx as usize].position_size;
-        let new_user_pos = old_user_pos.saturating_add(size);
-        let new_lp_pos = old_lp_pos.saturating_sub(size);
 
-        let user_inc = saturating_abs_i128(new_user_pos) > saturating_abs_i128(old_user_pos);
-        let lp_inc = saturating_abs_i128(new_lp_pos) > saturating_abs_i128(old_lp_pos);
-
-        if user_inc || lp_inc {
-            // Risk-increasing: require recent full sweep
-            self.require_recent_full_sweep(now_slot)?;
-            self.enforce_op(OpClass::RiskIncrease)?;
-        } else {
-            self.enforce_op(OpClass::RiskReduce)?;
-        }
+        let cash_before = self.debug_cash_snapshot();
+        let funding_settled = self
+            .debug_pending_funding(user_idx)
+            .saturating_add(self.debug_pending_funding(lp_idx));
 
         // Call matching engine
         let lp = &self.accounts[lp_idx as usize];
@@ -3297,37 +3750,13 @@ impl RiskEngine {
             (&mut right[0], &mut left[lp_idx as usize])
         };
 
-        // Calculate PNL impact from closing existing position
-        let mut user_pnl_delta = 0i128;
-        let mut lp_pnl_delta = 0i128;
-
-        if user.position_size != 0 {
-            let old_position = user.position_size;
-            let old_entry = user.entry_price;
-
-            if (old_position > 0 && exec_size < 0) || (old_position < 0 && exec_size > 0) {
-                let close_size = core::cmp::min(
-                    saturating_abs_i128(old_position),
-                    saturating_abs_i128(exec_size),
-                );
-                let price_diff = if old_position > 0 {
-                    (exec_price as i128).saturating_sub(old_entry as i128)
-                } else {
-                    (old_entry as i128).saturating_sub(exec_price as i128)
-                };
-
-                // Use saturating arithmetic (no overflow errors needed with Solana atomicity)
-                let pnl = price_diff
-                    .saturating_mul(close_size)
-                    .saturating_div(1_000_000);
-                user_pnl_delta = pnl;
-                lp_pnl_delta = -pnl;
-            }
-        }
+        // User side: new position, entry and PNL realized from closing existing position
+        let (new_user_position, new_user_entry, user_pnl_delta) =
+            Self::user_fill(user.position_size, user.entry_price, exec_price, exec_size)?;
+        let lp_pnl_delta = -user_pnl_delta;
 
-        // Calculate new positions
-        let new_user_position = user.position_size.saturating_add(exec_size);
-        let new_lp_position = lp.position_size.saturating_sub(exec_size);
+        let (new_lp_position, new_lp_entry) =
+            Self::lp_fill(lp.position_size, lp.entry_price, exec_price, exec_size)?;
 
         // Validate final position bounds (prevents overflow in mark_pnl calculations)
         if saturating_abs_i128(new_user_position) as u128 > MAX_POSITION_ABS
@@ -3336,54 +3765,6 @@ impl RiskEngine {
             return Err(RiskError::Overflow);
         }
 
-        // Calculate new entry prices
-        let mut new_user_entry = user.entry_price;
-        let mut new_lp_entry = lp.entry_price;
-
-        // Update user entry price
-        if (user.position_size > 0 && exec_size > 0) || (user.position_size < 0 && exec_size < 0) {
-            let old_notional = mul_u128(
-                saturating_abs_i128(user.position_size) as u128,
-                user.entry_price as u128,
-            );
-            let new_notional = mul_u128(saturating_abs_i128(exec_size) as u128, exec_price as u128);
-            let total_notional = add_u128(old_notional, new_notional);
-            let total_size = saturating_abs_i128(user.position_size)
-                .saturating_add(saturating_abs_i128(exec_size));
-            if total_size != 0 {
-                new_user_entry = div_u128(total_notional, total_size as u128)? as u64;
-            }
-        } else if saturating_abs_i128(user.position_size) < saturating_abs_i128(exec_size) {
-            new_user_entry = exec_price;
-        }
-
-        // Update LP entry price
-        // Bug #8 fix: Always update entry on sign flip, regardless of abs comparison
-        let lp_sign_flip = (lp.position_size > 0 && new_lp_position < 0)
-            || (lp.position_size < 0 && new_lp_position > 0);
-
-        if lp.position_size == 0 {
-            new_lp_entry = exec_price;
-        } else if lp_sign_flip && new_lp_position != 0 {
-            // Bug #8 fix: On any sign flip with nonzero new position, use exec_price
-            new_lp_entry = exec_price;
-        } else if (lp.position_size > 0 && new_lp_position > lp.position_size)
-            || (lp.position_size < 0 && new_lp_position < lp.position_size)
-        {
-            // Position expanding in same direction: weighted average entry
-            let old_notional = mul_u128(
-                saturating_abs_i128(lp.position_size) as u128,
-                lp.entry_price as u128,
-            );
-            let new_notional = mul_u128(saturating_abs_i128(exec_size) as u128, exec_price as u128);
-            let total_notional = add_u128(old_notional, new_notional);
-            let total_size = saturating_abs_i128(lp.position_size)
-                .saturating_add(saturating_abs_i128(exec_size));
-            if total_size != 0 {
-                new_lp_entry = div_u128(total_notional, total_size as u128)? as u64;
-            }
-        }
-
         // Compute final PNL values
         let new_user_pnl = user
             .pnl
@@ -3395,20 +3776,18 @@ impl RiskEngine {
         // FAIL-SAFE: overflow in mark_pnl => equity=0 => Undercollateralized (not generic Overflow)
         if new_user_position != 0 {
             // MTM equity = capital + new_realized_pnl + mark_pnl(new_pos, new_entry, oracle)
-            let user_equity_mtm = match Self::mark_pnl_for_position(new_user_position, new_user_entry, oracle_price) {
-                Ok(user_mark) => {
-                    let user_cap_i = u128_to_i128_clamped(user.capital);
-                    let user_eq_i = user_cap_i.saturating_add(new_user_pnl).saturating_add(user_mark);
-                    if user_eq_i > 0 { user_eq_i as u128 } else { 0 }
-                }
-                Err(_) => 0, // Overflow => worst-case equity => will fail margin check
-            };
-            let position_value = mul_u128(
-                saturating_abs_i128(new_user_position) as u128,
-                oracle_price as u128,
-            ) / 1_000_000;
-            let margin_required =
-                mul_u128(position_value, self.params.maintenance_margin_bps as u128) / 10_000;
+            let user_equity_mtm = Self::equity_mtm_parts(
+                user.capital,
+                new_user_pnl,
+                new_user_position,
+                new_user_entry,
+                oracle_price,
+            );
+            let margin_required = Self::margin_required_for(
+                new_user_position,
+                oracle_price,
+                self.params.maintenance_margin_bps,
+            );
             if user_equity_mtm <= margin_required {
                 return Err(RiskError::Undercollateralized);
             }
@@ -3418,20 +3797,18 @@ impl RiskEngine {
         // FAIL-SAFE: overflow in mark_pnl => equity=0 => Undercollateralized (not generic Overflow)
         if new_lp_position != 0 {
             // MTM equity = capital + new_realized_pnl + mark_pnl(new_pos, new_entry, oracle)
-            let lp_equity_mtm = match Self::mark_pnl_for_position(new_lp_position, new_lp_entry, oracle_price) {
-                Ok(lp_mark) => {
-                    let lp_cap_i = u128_to_i128_clamped(lp.capital);
-                    let lp_eq_i = lp_cap_i.saturating_add(new_lp_pnl).saturating_add(lp_mark);
-                    if lp_eq_i > 0 { lp_eq_i as u128 } else { 0 }
-                }
-                Err(_) => 0, // Overflow => worst-case equity => will fail margin check
-            };
-            let position_value = mul_u128(
-                saturating_abs_i128(new_lp_position) as u128,
-                oracle_price as u128,
-            ) / 1_000_000;
-            let margin_required =
-                mul_u128(position_value, self.params.maintenance_margin_bps as u128) / 10_000;
+            let lp_equity_mtm = Self::equity_mtm_parts(
+                lp.capital,
+                new_lp_pnl,
+                new_lp_position,
+                new_lp_entry,
+                oracle_price,
+            );
+            let margin_required = Self::margin_required_for(
+                new_lp_position,
+                oracle_price,
+                self.params.maintenance_margin_bps,
+            );
             if lp_equity_mtm <= margin_required {
                 return Err(RiskError::Undercollateralized);
             }
@@ -3440,6 +3817,7 @@ impl RiskEngine {
         // Commit all state changes
         self.insurance_fund.fee_revenue = add_u128(self.insurance_fund.fee_revenue, fee);
         self.insurance_fund.balance = add_u128(self.insurance_fund.balance, fee);
+        self.lifetime_volume = self.lifetime_volume.saturating_add(notional);
 
         // Credit fee to user's fee_credits (active traders earn credits that offset maintenance)
         user.fee_credits = user.fee_credits.saturating_add(fee as i128);
@@ -3488,6 +3866,9 @@ impl RiskEngine {
         self.settle_warmup_to_capital(user_idx)?;
         self.settle_warmup_to_capital(lp_idx)?;
 
+        // Fills net out between user and LP; fees move capital into insurance
+        self.debug_check_cash("execute_trade", cash_before, funding_settled);
+        self.debug_check_invariants("execute_trade", true);
         Ok(())
     }
 
@@ -4492,31 +4873,49 @@ impl RiskEngine {
     /// are rounded UP when accounts pay, ensuring the vault never has less than
     /// what's owed. The bounded dust check catches accidental minting bugs.
     pub fn check_conservation(&self) -> bool {
+        let (actual, expected) = self.conservation_totals();
+
+        // One-sided conservation check:
+        // actual >= expected (vault has at least what is owed)
+        // (actual - expected) <= MAX_ROUNDING_SLACK (bounded dust)
+        if actual < expected {
+            return false;
+        }
+        let slack = actual - expected;
+        slack <= MAX_ROUNDING_SLACK
+    }
+
+    /// Compute "would-be settled" PNL for an account. This accounts for lazy
+    /// funding settlement with same rounding as settle_account_funding.
+    fn settled_pnl(account: &Account, global_index: i128) -> i128 {
+        let mut settled_pnl = account.pnl;
+        if account.position_size != 0 {
+            let delta_f = global_index.saturating_sub(account.funding_index);
+            if delta_f != 0 {
+                // payment = position × ΔF / 1e6
+                // Round UP for positive (account pays), truncate for negative (account receives)
+                let raw = account.position_size.saturating_mul(delta_f);
+                let payment = if raw > 0 {
+                    raw.saturating_add(999_999).saturating_div(1_000_000)
+                } else {
+                    raw.saturating_div(1_000_000)
+                };
+                settled_pnl = settled_pnl.saturating_sub(payment);
+            }
+        }
+        settled_pnl
+    }
+
+    /// (vault + loss_accum, sum(capital) + sum(settled pnl) + insurance) as
+    /// used by check_conservation.
+    fn conservation_totals(&self) -> (u128, u128) {
         let mut total_capital = 0u128;
         let mut net_pnl: i128 = 0;
         let global_index = self.funding_index_qpb_e6;
 
         self.for_each_used(|_idx, account| {
             total_capital = add_u128(total_capital, account.capital);
-
-            // Compute "would-be settled" PNL for this account
-            // This accounts for lazy funding settlement with same rounding as settle_account_funding
-            let mut settled_pnl = account.pnl;
-            if account.position_size != 0 {
-                let delta_f = global_index.saturating_sub(account.funding_index);
-                if delta_f != 0 {
-                    // payment = position × ΔF / 1e6
-                    // Round UP for positive (account pays), truncate for negative (account receives)
-                    let raw = account.position_size.saturating_mul(delta_f);
-                    let payment = if raw > 0 {
-                        raw.saturating_add(999_999).saturating_div(1_000_000)
-                    } else {
-                        raw.saturating_div(1_000_000)
-                    };
-                    settled_pnl = settled_pnl.saturating_sub(payment);
-                }
-            }
-            net_pnl = net_pnl.saturating_add(settled_pnl);
+            net_pnl = net_pnl.saturating_add(Self::settled_pnl(account, global_index));
         });
 
         // Conservation formula:
@@ -4537,17 +4936,123 @@ impl RiskEngine {
         };
 
         let actual = add_u128(self.vault, self.loss_accum);
+        (actual, expected)
+    }
 
-        // One-sided conservation check:
-        // actual >= expected (vault has at least what is owed)
-        // (actual - expected) <= MAX_ROUNDING_SLACK (bounded dust)
-        if actual < expected {
-            return false;
+    /// Recompute the O(1) position aggregates from scratch and compare them
+    /// to the tracked values: total_open_interest == sum |pos| and
+    /// net_lp_pos == sum of LP positions.
+    pub fn check_position_aggregates(&self) -> bool {
+        let mut oi = 0u128;
+        let mut net_lp: i128 = 0;
+        self.for_each_used(|_idx, account| {
+            oi = add_u128(oi, saturating_abs_i128(account.position_size) as u128);
+            if account.is_lp() {
+                net_lp = net_lp.saturating_add(account.position_size);
+            }
+        });
+        oi == self.total_open_interest && net_lp == self.net_lp_pos
+    }
+
+    /// With the `debug-invariants` feature, panic at the call site if an
+    /// operation broke conservation or the position aggregates. Compiles to
+    /// nothing otherwise.
+    #[inline(always)]
+    fn debug_check_invariants(&self, _op: &'static str, _conservation: bool) {
+        #[cfg(feature = "debug-invariants")]
+        {
+            // Only the safety direction: forced closes at oracle realize one
+            // side without a counterparty, so slack can legitimately grow
+            let (actual, expected) = self.conservation_totals();
+            assert!(
+                !_conservation || actual >= expected,
+                "{}: vault short of obligations (vault + loss_accum {}, owed {})",
+                _op,
+                actual,
+                expected
+            );
+            assert!(
+                self.check_position_aggregates(),
+                "{}: position aggregates drifted (total_open_interest {}, net_lp_pos {})",
+                _op,
+                self.total_open_interest,
+                self.net_lp_pos
+            );
+        }
+    }
+
+    /// Snapshot balances for `debug_check_cash`. Compiles to nothing without
+    /// the `debug-invariants` feature.
+    #[inline(always)]
+    fn debug_cash_snapshot(&self) -> CashSnapshot {
+        #[cfg(feature = "debug-invariants")]
+        {
+            let mut accounts: i128 = 0;
+            self.for_each_used(|_idx, account| {
+                accounts = accounts
+                    .saturating_add(u128_to_i128_clamped(account.capital))
+                    .saturating_add(account.pnl);
+            });
+            CashSnapshot {
+                accounts,
+                insurance: self.insurance_fund.balance,
+                fee_revenue: self.insurance_fund.fee_revenue,
+                loss_accum: self.loss_accum,
+            }
+        }
+        #[cfg(not(feature = "debug-invariants"))]
+        CashSnapshot {}
+    }
+
+    /// Funding `touch_account` would settle into `idx` at the current global
+    /// index. Zero without the `debug-invariants` feature.
+    #[inline(always)]
+    fn debug_pending_funding(&self, _idx: u16) -> i128 {
+        #[cfg(feature = "debug-invariants")]
+        {
+            let account = &self.accounts[_idx as usize];
+            Self::settled_pnl(account, self.funding_index_qpb_e6).saturating_sub(account.pnl)
+        }
+        #[cfg(not(feature = "debug-invariants"))]
+        0
+    }
+
+    /// With the `debug-invariants` feature, panic unless an operation moved
+    /// exactly `_expected` into the pool sum(capital) + sum(pnl) + insurance -
+    /// loss_accum. Callers account for settled funding and ADL themselves.
+    #[inline(always)]
+    fn debug_check_cash(&self, _op: &'static str, _before: CashSnapshot, _expected: i128) {
+        #[cfg(feature = "debug-invariants")]
+        {
+            let after = self.debug_cash_snapshot();
+            let accounts_delta = after.accounts.saturating_sub(_before.accounts);
+            let fee_delta = u128_to_i128_clamped(after.fee_revenue)
+                .saturating_sub(u128_to_i128_clamped(_before.fee_revenue));
+            // Insurance movement other than fees: loss coverage, ADL, top-ups
+            let other_insurance_delta = u128_to_i128_clamped(after.insurance)
+                .saturating_sub(u128_to_i128_clamped(_before.insurance))
+                .saturating_sub(fee_delta);
+            let loss_delta = u128_to_i128_clamped(after.loss_accum)
+                .saturating_sub(u128_to_i128_clamped(_before.loss_accum));
+            let cash_delta = accounts_delta
+                .saturating_add(fee_delta)
+                .saturating_add(other_insurance_delta)
+                .saturating_sub(loss_delta);
+            assert!(
+                cash_delta == _expected,
+                "{}: cash delta {} (expected {}): accounts {}, fee revenue {}, other insurance {}, loss_accum {}",
+                _op,
+                cash_delta,
+                _expected,
+                accounts_delta,
+                fee_delta,
+                other_insurance_delta,
+                loss_delta
+            );
         }
-        let slack = actual - expected;
-        slack <= MAX_ROUNDING_SLACK
     }
 
+
     /// Advance to next slot (for testing warmup)
     pub fn advance_slot(&mut self, slots: u64) {
         self.current_slot = self.current_slot.saturating_add(slots);
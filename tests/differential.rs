@@ -0,0 +1,410 @@
+//! Differential tests: reference model vs risk engine
+//!
+//! Each scenario is run through a small, independently written reference model
+//! of the spec formulas and through the real `RiskEngine` code path, and the
+//! results must agree exactly. This catches silent divergence between the
+//! documented math (rounding direction, scaling) and the implementation.
+//!
+//! Run with: cargo test --features test --test differential
+
+use percolator::*;
+
+// ==============================================================================
+// DETERMINISTIC PRNG
+// ==============================================================================
+
+/// Simple xorshift64 PRNG for deterministic scenario generation
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn u64(&mut self, lo: u64, hi: u64) -> u64 {
+        if lo >= hi {
+            return lo;
+        }
+        lo + (self.next() % (hi - lo + 1))
+    }
+
+    fn i64(&mut self, lo: i64, hi: i64) -> i64 {
+        if lo >= hi {
+            return lo;
+        }
+        lo + (self.next() % ((hi - lo) as u64 + 1)) as i64
+    }
+
+    fn i128(&mut self, lo: i128, hi: i128) -> i128 {
+        if lo >= hi {
+            return lo;
+        }
+        lo + (self.next() as i128 % (hi - lo + 1))
+    }
+
+    fn u128(&mut self, lo: u128, hi: u128) -> u128 {
+        if lo >= hi {
+            return lo;
+        }
+        lo + (self.next() as u128 % (hi - lo + 1))
+    }
+}
+
+fn default_params() -> RiskParams {
+    RiskParams {
+        warmup_period_slots: 100,
+        maintenance_margin_bps: 500, // 5%
+        initial_margin_bps: 1000,    // 10%
+        trading_fee_bps: 10,         // 0.1%
+        max_accounts: 1000,
+        new_account_fee: 0,
+        risk_reduction_threshold: 0,
+        maintenance_fee_per_slot: 0,
+        max_crank_staleness_slots: u64::MAX,
+        liquidation_fee_bps: 50,
+        liquidation_fee_cap: 100_000,
+        liquidation_buffer_bps: 100,
+        min_liquidation_abs: 100_000,
+    }
+}
+
+// ==============================================================================
+// REFERENCE MODEL
+// ==============================================================================
+//
+// Written from the spec, deliberately using a different formulation than the
+// engine (sign/magnitude split instead of checked signed division) so that a
+// shared bug is unlikely.
+
+mod model {
+    /// ΔF = price × rate × dt / 10_000, truncated toward zero.
+    pub fn funding_delta(price: u64, rate_bps: i64, dt: u64) -> i128 {
+        let mag = (price as u128) * (rate_bps.unsigned_abs() as u128) * (dt as u128) / 10_000;
+        if rate_bps < 0 {
+            -(mag as i128)
+        } else {
+            mag as i128
+        }
+    }
+
+    /// Funding payment owed by an account (positive = account pays).
+    /// Rounded up when the account pays, truncated when it receives.
+    pub fn funding_payment(position: i128, delta_f: i128) -> i128 {
+        let raw = position * delta_f;
+        let mag = raw.unsigned_abs();
+        if raw > 0 {
+            mag.div_ceil(1_000_000) as i128
+        } else {
+            -((mag / 1_000_000) as i128)
+        }
+    }
+
+    /// mark_pnl = (oracle - entry) × pos / 1e6 for longs, mirrored for shorts,
+    /// truncated toward zero.
+    pub fn mark_pnl(pos: i128, entry: u64, oracle: u64) -> i128 {
+        if pos == 0 {
+            return 0;
+        }
+        let (gain, mag_diff) = if oracle >= entry {
+            (pos > 0, (oracle - entry) as u128)
+        } else {
+            (pos < 0, (entry - oracle) as u128)
+        };
+        let mag = (mag_diff * pos.unsigned_abs() / 1_000_000) as i128;
+        if gain {
+            mag
+        } else {
+            -mag
+        }
+    }
+
+    /// max(0, capital + pnl + mark_pnl)
+    pub fn equity_mtm(capital: u128, pnl: i128, pos: i128, entry: u64, oracle: u64) -> u128 {
+        let eq = capital as i128 + pnl + mark_pnl(pos, entry, oracle);
+        if eq > 0 {
+            eq as u128
+        } else {
+            0
+        }
+    }
+
+    /// equity > |pos| × oracle / 1e6 × bps / 10_000
+    pub fn above_margin(equity: u128, abs_pos: u128, oracle: u64, bps: u64) -> bool {
+        let notional = abs_pos * oracle as u128 / 1_000_000;
+        equity > notional * bps as u128 / 10_000
+    }
+
+    /// min(notional × fee_bps / 10_000, cap) on the closed size.
+    pub fn liquidation_fee(closed: u128, oracle: u64, fee_bps: u64, cap: u128) -> u128 {
+        let notional = closed * oracle as u128 / 1_000_000;
+        (notional * fee_bps as u128 / 10_000).min(cap)
+    }
+
+    /// Closed-form liquidation close amount (see spec §liquidation).
+    pub fn liquidation_close(
+        equity: u128,
+        abs_pos: u128,
+        oracle: u64,
+        maint_bps: u64,
+        buffer_bps: u64,
+        min_abs: u128,
+    ) -> (u128, bool) {
+        if abs_pos == 0 {
+            return (0, false);
+        }
+        let target = (maint_bps + buffer_bps) as u128;
+        let denom = oracle as u128 * target;
+        let safe = (equity * 10_000 * 1_000_000)
+            .checked_div(denom)
+            .unwrap_or(0)
+            .min(abs_pos);
+        let safe = safe.saturating_sub(1);
+        let close = abs_pos - safe;
+        if abs_pos - close < min_abs {
+            (abs_pos, true)
+        } else {
+            (close, close == abs_pos)
+        }
+    }
+}
+
+// ==============================================================================
+// FUNDING
+// ==============================================================================
+
+#[test]
+fn differential_funding_sequences() {
+    for seed in 1..=200u64 {
+        let mut rng = Rng::new(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+        let mut engine = Box::new(RiskEngine::new(default_params()));
+
+        let user = engine.add_user(0).unwrap();
+        let lp = engine.add_lp([0u8; 32], [0u8; 32], 0).unwrap();
+
+        // Zero-sum positions set directly; funding math only depends on position
+        let pos = rng.i128(-10_000_000_000, 10_000_000_000);
+        engine.accounts[user as usize].position_size = pos;
+        engine.accounts[lp as usize].position_size = -pos;
+
+        let mut model_index = 0i128;
+        let mut model_user_pnl = 0i128;
+        let mut model_lp_pnl = 0i128;
+        let mut slot = 0u64;
+
+        for _ in 0..20 {
+            let dt = rng.u64(0, 1_000);
+            let price = rng.u64(1, 1_000_000_000);
+            let rate = rng.i64(-100, 100);
+            slot += dt;
+
+            engine.accrue_funding(slot, price, rate).unwrap();
+            if dt > 0 {
+                model_index += model::funding_delta(price, rate, dt);
+            }
+            assert_eq!(
+                engine.funding_index_qpb_e6, model_index,
+                "seed {}: funding index diverged",
+                seed
+            );
+
+            // Touch accounts at random points so settlement spans multiple accruals
+            if rng.u64(0, 2) == 0 {
+                let before = engine.accounts[user as usize].funding_index;
+                engine.touch_account(user).unwrap();
+                model_user_pnl -= model::funding_payment(pos, model_index - before);
+            }
+            if rng.u64(0, 2) == 0 {
+                let before = engine.accounts[lp as usize].funding_index;
+                engine.touch_account(lp).unwrap();
+                model_lp_pnl -= model::funding_payment(-pos, model_index - before);
+            }
+
+            assert_eq!(engine.accounts[user as usize].pnl, model_user_pnl, "seed {}", seed);
+            assert_eq!(engine.accounts[lp as usize].pnl, model_lp_pnl, "seed {}", seed);
+        }
+
+        // Once both sides are fully settled, rounding must favor the vault
+        engine.touch_account(user).unwrap();
+        engine.touch_account(lp).unwrap();
+        assert!(
+            engine.accounts[user as usize].pnl + engine.accounts[lp as usize].pnl <= 0,
+            "seed {}: funding settlement minted value",
+            seed
+        );
+    }
+}
+
+// ==============================================================================
+// MARK PNL / EQUITY
+// ==============================================================================
+
+#[test]
+fn differential_mark_pnl() {
+    let mut rng = Rng::new(0xD1FF_0001);
+    for _ in 0..10_000 {
+        let pos = rng.i128(-1_000_000_000_000, 1_000_000_000_000);
+        let entry = rng.u64(1, 1_000_000_000);
+        let oracle = rng.u64(1, 1_000_000_000);
+
+        let engine_pnl = RiskEngine::mark_pnl_for_position(pos, entry, oracle).unwrap();
+        assert_eq!(
+            engine_pnl,
+            model::mark_pnl(pos, entry, oracle),
+            "pos={} entry={} oracle={}",
+            pos,
+            entry,
+            oracle
+        );
+    }
+}
+
+#[test]
+fn differential_equity_mtm() {
+    let mut rng = Rng::new(0xD1FF_0002);
+    let mut engine = Box::new(RiskEngine::new(default_params()));
+    let user = engine.add_user(0).unwrap();
+
+    for _ in 0..10_000 {
+        let capital = rng.u128(0, 1_000_000_000);
+        let pnl = rng.i128(-1_000_000_000, 1_000_000_000);
+        let pos = rng.i128(-10_000_000_000, 10_000_000_000);
+        let entry = rng.u64(1, 10_000_000);
+        let oracle = rng.u64(1, 10_000_000);
+
+        let acct = &mut engine.accounts[user as usize];
+        acct.capital = capital;
+        acct.pnl = pnl;
+        acct.position_size = pos;
+        acct.entry_price = entry;
+
+        let acct = &engine.accounts[user as usize];
+        assert_eq!(
+            engine.account_equity_mtm_at_oracle(acct, oracle),
+            model::equity_mtm(capital, pnl, pos, entry, oracle)
+        );
+    }
+}
+
+// ==============================================================================
+// LIQUIDATION
+// ==============================================================================
+
+#[test]
+fn differential_liquidation_close_amount() {
+    let mut rng = Rng::new(0xD1FF_0003);
+
+    for _ in 0..2_000 {
+        let mut params = default_params();
+        params.maintenance_margin_bps = rng.u64(1, 2_000);
+        params.liquidation_buffer_bps = rng.u64(0, 500);
+        params.min_liquidation_abs = rng.u128(0, 1_000_000);
+
+        let mut engine = Box::new(RiskEngine::new(params));
+        let user = engine.add_user(0).unwrap();
+
+        let capital = rng.u128(0, 100_000_000);
+        let pnl = rng.i128(-50_000_000, 50_000_000);
+        let pos = rng.i128(-10_000_000_000, 10_000_000_000);
+        let entry = rng.u64(1, 10_000_000);
+        let oracle = rng.u64(1, 10_000_000);
+
+        let acct = &mut engine.accounts[user as usize];
+        acct.capital = capital;
+        acct.pnl = pnl;
+        acct.position_size = pos;
+        acct.entry_price = entry;
+
+        let acct = &engine.accounts[user as usize];
+        let got = engine.compute_liquidation_close_amount(acct, oracle);
+        let want = model::liquidation_close(
+            model::equity_mtm(capital, pnl, pos, entry, oracle),
+            pos.unsigned_abs(),
+            oracle,
+            params.maintenance_margin_bps,
+            params.liquidation_buffer_bps,
+            params.min_liquidation_abs,
+        );
+        assert_eq!(
+            got, want,
+            "cap={} pnl={} pos={} entry={} oracle={}",
+            capital, pnl, pos, entry, oracle
+        );
+    }
+}
+
+#[test]
+fn differential_liquidation_at_oracle() {
+    // Run the engine's liquidation and check its close amount, fee and
+    // post-state against the model; the remainder must sit above target margin.
+    let mut rng = Rng::new(0xD1FF_0004);
+    let params = default_params();
+    let target_bps = params.maintenance_margin_bps + params.liquidation_buffer_bps;
+    let mut liquidated = 0;
+
+    for _ in 0..5_000 {
+        let capital = rng.u128(1, 100_000_000);
+        let pos = rng.i128(-10_000_000_000, 10_000_000_000);
+        let oracle = rng.u64(1, 10_000_000);
+
+        let mut engine = Box::new(RiskEngine::new(params));
+        let user = engine.add_user(0).unwrap();
+        engine.deposit(user, capital).unwrap();
+        // Entry == oracle so the close itself realizes no PnL and equity is preserved
+        engine.accounts[user as usize].position_size = pos;
+        engine.accounts[user as usize].entry_price = oracle;
+        engine.total_open_interest = pos.unsigned_abs();
+
+        let equity = model::equity_mtm(capital, 0, pos, oracle, oracle);
+        let abs_pos = pos.unsigned_abs();
+        let did = engine.liquidate_at_oracle(user, 0, oracle).unwrap();
+        let acct = &engine.accounts[user as usize];
+
+        if model::above_margin(equity, abs_pos, oracle, params.maintenance_margin_bps) {
+            assert!(!did, "pos={} cap={} oracle={}: healthy account liquidated", pos, capital, oracle);
+            assert_eq!(acct.position_size, pos);
+            assert_eq!(acct.capital, capital);
+            continue;
+        }
+        assert!(did, "pos={} cap={} oracle={}: liquidation skipped", pos, capital, oracle);
+        liquidated += 1;
+
+        let (close, full) = model::liquidation_close(
+            equity,
+            abs_pos,
+            oracle,
+            params.maintenance_margin_bps,
+            params.liquidation_buffer_bps,
+            params.min_liquidation_abs,
+        );
+        let remaining = abs_pos - close;
+        let fee = model::liquidation_fee(close, oracle, params.liquidation_fee_bps, params.liquidation_fee_cap)
+            .min(capital);
+
+        let ctx = (pos, capital, oracle);
+        assert_eq!(acct.position_size.unsigned_abs(), remaining, "{:?}: close amount", ctx);
+        assert_eq!(full, remaining == 0, "{:?}", ctx);
+        if remaining > 0 {
+            assert_eq!(acct.position_size.signum(), pos.signum(), "{:?}: side flipped", ctx);
+            // Margin target is checked before the fee is taken
+            assert!(
+                model::above_margin(equity, remaining, oracle, target_bps),
+                "{:?}: remainder below target",
+                ctx
+            );
+        }
+        assert_eq!(acct.pnl, 0, "{:?}", ctx);
+        assert_eq!(acct.capital, capital - fee, "{:?}: fee", ctx);
+        assert_eq!(engine.insurance_fund.balance, fee, "{:?}: fee to insurance", ctx);
+        assert_eq!(engine.vault, capital, "{:?}: vault untouched", ctx);
+    }
+    assert!(liquidated > 100, "too few liquidations exercised: {}", liquidated);
+}
@@ -0,0 +1,187 @@
+//! Stack and heap usage checks for engine operations
+//!
+//! On-chain the engine runs with a 4KB-per-frame BPF stack and a 32KB bump
+//! heap that is never freed. These tests guard the two regressions that are
+//! easy to introduce and hard to spot in review:
+//!
+//! - Heap: every engine operation must perform zero allocations. The crate is
+//!   `no_std` without `alloc` today; a counting global allocator catches any
+//!   dependency or refactor that changes that.
+//! - Stack: each operation runs on a thread with a small fixed stack. A large
+//!   by-value copy (an `Account` array, a whole `RiskEngine`) or deep recursion
+//!   overflows it, and the abort message names the offending operation's
+//!   thread. The budget is 16KB, the smallest thread stack the platform
+//!   hands out (PTHREAD_STACK_MIN on Linux); smaller requests are rounded up.
+//!   That is a whole-operation bound on native debug frames, so it cannot
+//!   track BPF's 4KB-per-frame limit. Per-frame limits are left to
+//!   `cargo build-sbf`, which reports any function whose frame exceeds 4KB.
+//!
+//! Run with: cargo test --features test --test resource_usage
+
+use percolator::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// ==============================================================================
+// COUNTING ALLOCATOR
+// ==============================================================================
+
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCS.try_with(|c| c.set(c.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Allocations performed by `f` on the current thread
+fn count_allocs<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCS.with(|c| c.get());
+    let r = f();
+    let after = ALLOCS.with(|c| c.get());
+    (r, after - before)
+}
+
+// ==============================================================================
+// HARNESS
+// ==============================================================================
+
+/// Native stack budget per operation (debug build); the platform minimum
+const STACK_BUDGET: usize = 16 * 1024;
+
+fn params() -> RiskParams {
+    RiskParams {
+        warmup_period_slots: 100,
+        maintenance_margin_bps: 500,
+        initial_margin_bps: 1000,
+        trading_fee_bps: 10,
+        max_accounts: MAX_ACCOUNTS as u64,
+        new_account_fee: 0,
+        risk_reduction_threshold: 0,
+        maintenance_fee_per_slot: 1,
+        max_crank_staleness_slots: u64::MAX,
+        liquidation_fee_bps: 50,
+        liquidation_fee_cap: 100_000,
+        liquidation_buffer_bps: 100,
+        min_liquidation_abs: 100_000,
+    }
+}
+
+/// Engine with an LP and several leveraged users. Built on a large-stack
+/// thread: RiskEngine::new returns the full engine by value, which exceeds
+/// the default test thread stack at production MAX_ACCOUNTS.
+fn populated() -> (Box<RiskEngine>, u16, Vec<u16>) {
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(build_populated)
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+fn build_populated() -> (Box<RiskEngine>, u16, Vec<u16>) {
+    let mut engine = Box::new(RiskEngine::new(params()));
+    let lp = engine.add_lp([1u8; 32], [2u8; 32], 0).unwrap();
+    engine.deposit(lp, 1_000_000_000_000).unwrap();
+    let mut users = Vec::new();
+    for i in 0..8 {
+        let u = engine.add_user(0).unwrap();
+        engine.deposit(u, 1_000_000).unwrap();
+        let size: i128 = if i % 2 == 0 { 9_000_000 } else { -9_000_000 };
+        engine
+            .execute_trade(&NoOpMatcher, lp, u, 0, 1_000_000, size)
+            .unwrap();
+        users.push(u);
+    }
+    (engine, lp, users)
+}
+
+/// Run `op` on a fresh small-stack thread and assert it does not allocate.
+/// A stack overflow aborts the test binary with "thread '<name>' has
+/// overflowed its stack".
+fn check<F>(name: &str, op: F)
+where
+    F: FnOnce(&mut RiskEngine, u16, &[u16]) + Send + 'static,
+{
+    let (mut engine, lp, users) = populated();
+    let label = name.to_string();
+    let allocs = std::thread::Builder::new()
+        .name(name.to_string())
+        .stack_size(STACK_BUDGET)
+        .spawn(move || count_allocs(|| op(&mut engine, lp, &users)).1)
+        .unwrap()
+        .join()
+        .unwrap_or_else(|_| panic!("{} panicked", label));
+    assert_eq!(allocs, 0, "{} performed {} heap allocation(s)", name, allocs);
+}
+
+// ==============================================================================
+// TESTS
+// ==============================================================================
+
+#[test]
+fn execute_trade_fits_budget() {
+    check("execute_trade", |e, lp, users| {
+        e.execute_trade(&NoOpMatcher, lp, users[0], 1, 1_000_000, 1_000)
+            .unwrap();
+        e.execute_trade(&NoOpMatcher, lp, users[0], 1, 1_000_000, -1_000)
+            .unwrap();
+    });
+}
+
+#[test]
+fn keeper_crank_full_sweep_fits_budget() {
+    check("keeper_crank", |e, _lp, _users| {
+        // 8% drop: longs are liquidated during the sweep
+        for step in 0..NUM_STEPS as u64 {
+            e.keeper_crank(u16::MAX, 1 + step, 920_000, 0, false)
+                .unwrap();
+        }
+    });
+}
+
+#[test]
+fn liquidate_at_oracle_fits_budget() {
+    check("liquidate_at_oracle", |e, _lp, users| {
+        let _ = e.liquidate_at_oracle(users[0], 1, 920_000).unwrap();
+    });
+}
+
+#[test]
+fn funding_and_touch_fit_budget() {
+    check("accrue_funding", |e, _lp, users| {
+        e.accrue_funding(100, 1_000_000, 5).unwrap();
+        for &u in users {
+            e.touch_account(u).unwrap();
+        }
+    });
+}
+
+#[test]
+fn deposit_withdraw_close_fit_budget() {
+    check("withdraw", |e, _lp, _users| {
+        let u = e.add_user(0).unwrap();
+        e.deposit(u, 1_000).unwrap();
+        e.withdraw(u, 500, 1, 1_000_000).unwrap();
+        e.close_account(u, 1, 1_000_000).unwrap();
+    });
+}
+
+#[test]
+fn panic_settle_all_fits_budget() {
+    check("panic_settle_all", |e, _lp, _users| {
+        e.panic_settle_all(900_000).unwrap();
+    });
+}
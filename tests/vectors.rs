@@ -0,0 +1,104 @@
+//! Shared margin-math vectors (tests/vectors/margin.txt)
+//!
+//! The same file is checked by the CLI's TypeScript mirror of the margin
+//! math (cli/test/vectors.test.ts). Any change to mark-to-market, fee or
+//! entry-price rules must update the vectors, which then fails the CLI tests
+//! until the mirror is updated too.
+//!
+//! Run with: cargo test --features test --test vectors
+
+use percolator::*;
+
+const VECTORS: &str = include_str!("vectors/margin.txt");
+
+fn params() -> RiskParams {
+    RiskParams {
+        warmup_period_slots: 100,
+        maintenance_margin_bps: 500,
+        initial_margin_bps: 1000,
+        trading_fee_bps: 10,
+        max_accounts: MAX_ACCOUNTS as u64,
+        new_account_fee: 0,
+        risk_reduction_threshold: 0,
+        maintenance_fee_per_slot: 0,
+        max_crank_staleness_slots: u64::MAX,
+        liquidation_fee_bps: 50,
+        liquidation_fee_cap: 100_000,
+        liquidation_buffer_bps: 100,
+        min_liquidation_abs: 100_000,
+    }
+}
+
+/// Vector lines of the given kind, split into fields (kind stripped)
+fn vectors(kind: &str) -> Vec<(usize, Vec<&'static str>)> {
+    VECTORS
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.starts_with('#') && !l.trim().is_empty())
+        .map(|(n, l)| (n + 1, l.split_whitespace().collect::<Vec<_>>()))
+        .filter(|(_, f)| f[0] == kind)
+        .map(|(n, f)| (n, f[1..].to_vec()))
+        .collect()
+}
+
+#[test]
+fn mark_pnl_vectors() {
+    let cases = vectors("mark_pnl");
+    assert!(!cases.is_empty());
+    for (line, f) in cases {
+        let pos: i128 = f[0].parse().unwrap();
+        let entry: u64 = f[1].parse().unwrap();
+        let oracle: u64 = f[2].parse().unwrap();
+        let expected: i128 = f[3].parse().unwrap();
+        let got = RiskEngine::mark_pnl_for_position(pos, entry, oracle).unwrap();
+        assert_eq!(got, expected, "margin.txt:{}", line);
+    }
+}
+
+#[test]
+fn trade_vectors() {
+    let cases = vectors("trade");
+    assert!(!cases.is_empty());
+    for (line, f) in cases {
+        let capital: u128 = f[0].parse().unwrap();
+        let fills: Vec<(i128, u64)> = f[1]
+            .split(',')
+            .map(|s| {
+                let (size, price) = s.split_once('@').unwrap();
+                (size.parse().unwrap(), price.parse().unwrap())
+            })
+            .collect();
+        let exp_pos: i128 = f[2].parse().unwrap();
+        let exp_entry: u64 = f[3].parse().unwrap();
+        let exp_equity: u128 = f[4].parse().unwrap();
+        let exp_ok = f[5] == "ok";
+
+        let mut engine = Box::new(RiskEngine::new(params()));
+        let lp = engine.add_lp([1u8; 32], [2u8; 32], 0).unwrap();
+        engine.deposit(lp, 1_000_000_000_000).unwrap();
+        let user = engine.add_user(0).unwrap();
+        engine.deposit(user, capital).unwrap();
+
+        let mut last_ok = true;
+        for (i, &(size, price)) in fills.iter().enumerate() {
+            let res = engine.execute_trade(&NoOpMatcher, lp, user, 0, price, size);
+            if i + 1 < fills.len() {
+                res.unwrap_or_else(|e| panic!("margin.txt:{} fill {}: {:?}", line, i, e));
+            } else {
+                last_ok = res.is_ok();
+            }
+        }
+
+        let last_price = fills.last().unwrap().1;
+        let acct = &engine.accounts[user as usize];
+        assert_eq!(last_ok, exp_ok, "margin.txt:{} accepted", line);
+        assert_eq!(acct.position_size, exp_pos, "margin.txt:{} position", line);
+        assert_eq!(acct.entry_price, exp_entry, "margin.txt:{} entry", line);
+        assert_eq!(
+            engine.account_equity_mtm_at_oracle(acct, last_price),
+            exp_equity,
+            "margin.txt:{} equity",
+            line
+        );
+    }
+}